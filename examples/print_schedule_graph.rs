@@ -0,0 +1,11 @@
+use bevy::prelude::*;
+
+use bevy_mod_debugdump::schedule_graph_dot;
+
+fn main() {
+    let mut app = App::build();
+    app.add_plugins(DefaultPlugins);
+
+    let dot = schedule_graph_dot(&app.app.schedule);
+    println!("{}", dot);
+}
@@ -0,0 +1,12 @@
+use bevy::prelude::*;
+use bevy_egui::EguiPlugin;
+use bevy_mod_debugdump::RenderGraphViewerPlugin;
+
+// press F9 to toggle the viewer
+fn main() {
+    App::build()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(EguiPlugin)
+        .add_plugin(RenderGraphViewerPlugin)
+        .run();
+}
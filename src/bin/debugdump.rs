@@ -0,0 +1,133 @@
+//! Converts a snapshot saved with `save_snapshot` or `save_graph_snapshot`, so the layout
+//! doesn't have to happen inside of the game.
+//!
+//! ```text
+//! debugdump <snapshot.json> [--format dot|svg|mermaid] [--output <file>]
+//! ```
+//! The format defaults to the extension of the output file, or dot when writing to stdout.
+//! `svg` needs graphviz's `dot` command to be installed.
+
+use bevy_mod_debugdump::{load_snapshot, DotExporter, DotSettings, GraphExporter, MermaidExporter};
+use std::{
+    env,
+    error::Error,
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    process::{self, Command, Stdio},
+};
+
+const USAGE: &str = "usage: debugdump <snapshot.json> [--format dot|svg|mermaid] [--output <file>]";
+
+enum Format {
+    Dot,
+    Svg,
+    Mermaid,
+}
+
+impl Format {
+    fn parse(name: &str) -> Option<Format> {
+        match name {
+            "dot" | "gv" => Some(Format::Dot),
+            "svg" => Some(Format::Svg),
+            "mermaid" | "mmd" => Some(Format::Mermaid),
+            _ => None,
+        }
+    }
+}
+
+struct Args {
+    snapshot: PathBuf,
+    format: Format,
+    output: Option<PathBuf>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut snapshot = None;
+    let mut format = None;
+    let mut output = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-f" | "--format" => {
+                let name = args.next().ok_or("missing value for --format")?;
+                format = Some(Format::parse(&name).ok_or(format!("unknown format `{}`", name))?);
+            }
+            "-o" | "--output" => {
+                output = Some(PathBuf::from(
+                    args.next().ok_or("missing value for --output")?,
+                ));
+            }
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ if snapshot.is_none() => snapshot = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+
+    let format = match (format, &output) {
+        (Some(format), _) => format,
+        (None, Some(output)) => output
+            .extension()
+            .and_then(|extension| Format::parse(&extension.to_string_lossy()))
+            .unwrap_or(Format::Dot),
+        (None, None) => Format::Dot,
+    };
+    Ok(Args {
+        snapshot: snapshot.ok_or(USAGE)?,
+        format,
+        output,
+    })
+}
+
+fn run(args: &Args) -> Result<(), Box<dyn Error>> {
+    let graph = load_snapshot(&args.snapshot)?;
+    let settings = DotSettings::default();
+
+    let contents = match args.format {
+        Format::Dot => DotExporter::new(&settings)
+            .export_to_string(&graph)
+            .into_bytes(),
+        Format::Svg => render_svg(&DotExporter::new(&settings).export_to_string(&graph))?,
+        Format::Mermaid => MermaidExporter.export_to_string(&graph).into_bytes(),
+    };
+
+    match &args.output {
+        Some(path) => fs::write(path, &contents)?,
+        None => io::stdout().write_all(&contents)?,
+    }
+    Ok(())
+}
+
+fn render_svg(dot: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut child = Command::new("dot")
+        .arg("-Tsvg")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|error| format!("failed to run graphviz's `dot`: {}", error))?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(dot.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(format!("`dot` exited with {}", output.status).into());
+    }
+    Ok(output.stdout)
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
+        }
+    };
+    if let Err(error) = run(&args) {
+        eprintln!("error: {}", error);
+        process::exit(1);
+    }
+}
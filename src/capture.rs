@@ -0,0 +1,159 @@
+use crate::{
+    graph::DebugGraph,
+    render_graph::{render_graph_dot_with_settings, RenderGraphSettings},
+};
+use bevy::{prelude::*, render::render_graph::RenderGraph, utils::HashSet};
+use std::{
+    fmt::Write,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Records the render graph every `every` frames during the first `frames` frames,
+/// to observe graphs which change at runtime, e.g. when nodes are added for new windows or cameras.
+///
+/// Writes a numbered sequence of dot files into `dir`, like `render_graph_0060.dot` for frame 60,
+/// and once the capture is complete a `summary.txt` listing the nodes and edges added or removed
+/// between the captures.
+pub struct RenderGraphCapturePlugin {
+    pub dir: PathBuf,
+    pub settings: RenderGraphSettings,
+    pub every: u32,
+    pub frames: u32,
+}
+
+impl RenderGraphCapturePlugin {
+    pub fn new(dir: impl Into<PathBuf>, every: u32, frames: u32) -> RenderGraphCapturePlugin {
+        RenderGraphCapturePlugin {
+            dir: dir.into(),
+            settings: RenderGraphSettings::default(),
+            every,
+            frames,
+        }
+    }
+}
+
+impl Plugin for RenderGraphCapturePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.insert_resource(RenderGraphCapture {
+            dir: self.dir.clone(),
+            settings: self.settings.clone(),
+            every: self.every.max(1),
+            frames: self.frames,
+            frame: 0,
+            previous: None,
+            summary: String::new(),
+        })
+        .add_system(capture_render_graph.system());
+    }
+}
+
+struct RenderGraphCapture {
+    dir: PathBuf,
+    settings: RenderGraphSettings,
+    every: u32,
+    frames: u32,
+    frame: u32,
+    /// the graph of the last capture and its frame, to diff the next one against
+    previous: Option<(u32, DebugGraph)>,
+    summary: String,
+}
+
+fn capture_render_graph(render_graph: Res<RenderGraph>, mut capture: ResMut<RenderGraphCapture>) {
+    let frame = capture.frame;
+    if frame >= capture.frames {
+        return;
+    }
+    capture.frame += 1;
+
+    if frame % capture.every == 0 {
+        let graph = DebugGraph::from_render_graph(&render_graph);
+        let changes = match &capture.previous {
+            Some((previous_frame, previous)) => format!(
+                "frame {} (since frame {}):\n{}",
+                frame,
+                previous_frame,
+                diff(previous, &graph)
+            ),
+            None => format!(
+                "frame {}: {} nodes, {} edges\n",
+                frame,
+                graph.nodes.len(),
+                graph.edges.len()
+            ),
+        };
+        capture.summary.push_str(&changes);
+        capture.previous = Some((frame, graph));
+
+        let path = capture.dir.join(format!("render_graph_{:04}.dot", frame));
+        let dot = render_graph_dot_with_settings(&render_graph, &capture.settings);
+        if let Err(error) = fs::create_dir_all(&capture.dir).and_then(|_| fs::write(&path, dot)) {
+            bevy::log::error!("failed to write {}: {}", path.display(), error);
+        }
+    }
+
+    if capture.frame == capture.frames {
+        let path = capture.dir.join("summary.txt");
+        match write_summary(&capture, &path) {
+            Ok(()) => bevy::log::info!("render graph capture complete, wrote {}", path.display()),
+            Err(error) => bevy::log::error!("failed to write {}: {}", path.display(), error),
+        }
+    }
+}
+
+fn write_summary(capture: &RenderGraphCapture, path: &Path) -> io::Result<()> {
+    fs::create_dir_all(&capture.dir)?;
+    fs::write(path, &capture.summary)
+}
+
+/// the nodes and edges added and removed between the graphs, one per line
+///
+/// Nodes are compared by their name, as the ids derived for the dot output change
+/// whenever another node with the same name is added or removed.
+fn diff(previous: &DebugGraph, current: &DebugGraph) -> String {
+    let nodes = |graph: &DebugGraph| -> HashSet<String> {
+        graph
+            .nodes
+            .iter()
+            .map(|node| format!("{} ({})", node.name, node.type_name))
+            .collect()
+    };
+    let edges = |graph: &DebugGraph| -> HashSet<String> {
+        let name = |id: &str| graph.node(id).map_or(id, |node| node.name.as_str());
+        graph
+            .edges
+            .iter()
+            .map(|edge| {
+                format!(
+                    "{} -> {} ({:?})",
+                    name(&edge.from),
+                    name(&edge.to),
+                    edge.kind
+                )
+            })
+            .collect()
+    };
+
+    let mut changes = String::new();
+    for (kind, previous, current) in [
+        ("node", nodes(previous), nodes(current)),
+        ("edge", edges(previous), edges(current)),
+    ]
+    .iter()
+    {
+        let mut added: Vec<_> = current.difference(previous).collect();
+        let mut removed: Vec<_> = previous.difference(current).collect();
+        added.sort();
+        removed.sort();
+        for name in added {
+            writeln!(changes, "  + {} {}", kind, name).unwrap();
+        }
+        for name in removed {
+            writeln!(changes, "  - {} {}", kind, name).unwrap();
+        }
+    }
+    if changes.is_empty() {
+        changes.push_str("  unchanged\n");
+    }
+    changes
+}
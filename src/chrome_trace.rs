@@ -0,0 +1,102 @@
+use crate::graph::{DebugGraph, GraphExporter};
+use bevy::utils::HashMap;
+use std::{
+    io::{self, Write},
+    time::Duration,
+};
+
+/// Writes the execution order of the graph as a `chrome://tracing` / Perfetto json trace,
+/// so the static structure can be lined up with a recorded timeline.
+///
+/// The nodes run one after the other in their [`DebugGraph::topological_order`].
+/// Nodes without a recorded timing get [`ChromeTraceExporter::default_duration`].
+#[derive(Debug, Clone)]
+pub struct ChromeTraceExporter {
+    /// recorded durations, by node id or name
+    pub timings: HashMap<String, Duration>,
+    pub default_duration: Duration,
+}
+
+impl Default for ChromeTraceExporter {
+    fn default() -> Self {
+        ChromeTraceExporter {
+            timings: HashMap::default(),
+            default_duration: Duration::from_micros(100),
+        }
+    }
+}
+
+impl ChromeTraceExporter {
+    pub fn with_timings(timings: HashMap<String, Duration>) -> Self {
+        ChromeTraceExporter {
+            timings,
+            ..Default::default()
+        }
+    }
+
+    fn timing(&self, id: &str, name: &str) -> Option<Duration> {
+        self.timings
+            .get(id)
+            .or_else(|| self.timings.get(name))
+            .copied()
+    }
+}
+
+impl GraphExporter for ChromeTraceExporter {
+    fn export(&self, graph: &DebugGraph, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(writer, "{{\"traceEvents\":[")?;
+        writeln!(
+            writer,
+            "  {{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":0,\"tid\":0,\"args\":{{\"name\":{}}}}}",
+            string(&graph.name)
+        )?;
+
+        let mut timestamp = Duration::default();
+        for (position, node) in graph.topological_order().into_iter().enumerate() {
+            let recorded = self.timing(&node.id, &node.name);
+            let duration = recorded.unwrap_or(self.default_duration);
+            let category = node
+                .cluster
+                .as_deref()
+                .and_then(|id| graph.clusters.iter().find(|cluster| cluster.id == id))
+                .map_or(graph.name.as_str(), |cluster| cluster.label.as_str());
+            writeln!(
+                writer,
+                "  ,{{\"name\":{},\"cat\":{},\"ph\":\"X\",\"pid\":0,\"tid\":0,\"ts\":{},\"dur\":{},\
+                 \"args\":{{\"order\":{},\"type\":{},\"recorded\":{}}}}}",
+                string(&node.name),
+                string(category),
+                micros(timestamp),
+                micros(duration),
+                position + 1,
+                string(&node.type_name),
+                recorded.is_some(),
+            )?;
+            timestamp += duration;
+        }
+
+        writeln!(writer, "],\"displayTimeUnit\":\"ms\"}}")
+    }
+}
+
+/// trace timestamps are in microseconds
+fn micros(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64() * 1_000_000.0)
+}
+
+/// json string literal
+fn string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
@@ -0,0 +1,64 @@
+use crate::render_graph_dot;
+use bevy::{prelude::*, render::render_graph::RenderGraph};
+
+pub use arboard::Error as ClipboardError;
+
+/// Places the text on the system clipboard, e.g. to paste the dot output into an online graphviz viewer.
+///
+/// On Linux/X11 the clipboard contents are owned by the program that copied them, and they are lost
+/// when the clipboard created here is dropped at the end of the call. Use the [`CopyToClipboardPlugin`]
+/// there, which keeps its clipboard alive as long as the app runs.
+pub fn copy_to_clipboard(text: &str) -> Result<(), ClipboardError> {
+    arboard::Clipboard::new()?.set_text(text.to_string())
+}
+
+/// Copies the dot output of the render graph to the clipboard when pressing `key`.
+pub struct CopyToClipboardPlugin {
+    pub key: KeyCode,
+}
+
+impl Default for CopyToClipboardPlugin {
+    fn default() -> Self {
+        CopyToClipboardPlugin { key: KeyCode::F10 }
+    }
+}
+
+impl Plugin for CopyToClipboardPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        // the clipboard isn't guaranteed to be `Send`
+        app.insert_non_send_resource(ClipboardState {
+            key: self.key,
+            clipboard: None,
+        })
+        .add_system(copy_render_graph.system());
+    }
+}
+
+struct ClipboardState {
+    key: KeyCode,
+    /// kept alive after copying, as X11 loses the contents when the clipboard is dropped
+    clipboard: Option<arboard::Clipboard>,
+}
+
+fn copy_render_graph(
+    keyboard: Res<Input<KeyCode>>,
+    mut state: NonSendMut<ClipboardState>,
+    render_graph: Res<RenderGraph>,
+) {
+    if !keyboard.just_pressed(state.key) {
+        return;
+    }
+    if state.clipboard.is_none() {
+        state.clipboard = arboard::Clipboard::new()
+            .map_err(|error| bevy::log::error!("failed to open clipboard: {}", error))
+            .ok();
+    }
+    let clipboard = match &mut state.clipboard {
+        Some(clipboard) => clipboard,
+        None => return,
+    };
+    match clipboard.set_text(render_graph_dot(&render_graph)) {
+        Ok(()) => bevy::log::info!("copied render graph to clipboard"),
+        Err(error) => bevy::log::error!("failed to copy render graph to clipboard: {}", error),
+    }
+}
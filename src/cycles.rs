@@ -0,0 +1,223 @@
+use crate::render_graph::sorted_nodes;
+use bevy::{
+    render::render_graph::{Edge, NodeId, RenderGraph},
+    utils::HashMap,
+};
+
+/// A set of nodes which (transitively) depend on each other.
+#[derive(Debug, Clone)]
+pub struct RenderGraphCycle {
+    pub nodes: Vec<NodeId>,
+    /// the edges between `nodes`, which make up the cycle
+    pub edges: Vec<Edge>,
+}
+
+impl RenderGraphCycle {
+    pub fn contains_edge(&self, edge: &Edge) -> bool {
+        self.edges.contains(edge)
+    }
+}
+
+/// returns `(output_node, input_node)`, i.e. the direction data flows along the edge
+pub(crate) fn edge_nodes(edge: &Edge) -> (NodeId, NodeId) {
+    match *edge {
+        Edge::SlotEdge {
+            input_node,
+            output_node,
+            ..
+        } => (output_node, input_node),
+        Edge::NodeEdge {
+            input_node,
+            output_node,
+        } => (output_node, input_node),
+    }
+}
+
+/// Finds the strongly connected components of the graph containing more than one node
+/// (or a node depending on itself). A render graph with cycles can't be executed.
+pub fn find_cycles(graph: &RenderGraph) -> Vec<RenderGraphCycle> {
+    let nodes = sorted_nodes(graph);
+    let indices: HashMap<NodeId, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(index, node)| (node.id, index))
+        .collect();
+
+    let successors: Vec<Vec<usize>> = nodes
+        .iter()
+        .map(|node| {
+            node.edges
+                .output_edges
+                .iter()
+                .map(|edge| indices[&edge_nodes(edge).1])
+                .collect()
+        })
+        .collect();
+
+    let mut components: Vec<Vec<usize>> = strongly_connected_components(&successors)
+        .into_iter()
+        .filter(|component| component.len() > 1 || successors[component[0]].contains(&component[0]))
+        .collect();
+    components.sort();
+
+    components
+        .into_iter()
+        .map(|component| {
+            let edges = component
+                .iter()
+                .flat_map(|&index| &nodes[index].edges.output_edges)
+                .filter(|edge| component.contains(&indices[&edge_nodes(edge).1]))
+                .cloned()
+                .collect();
+            RenderGraphCycle {
+                nodes: component.iter().map(|&index| nodes[index].id).collect(),
+                edges,
+            }
+        })
+        .collect()
+}
+
+/// Tarjan's algorithm on the successor lists of the nodes,
+/// returns the sorted components in the order they are completed, successors before predecessors
+fn strongly_connected_components(successors: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut tarjan = Tarjan {
+        successors,
+        index: 0,
+        indices: vec![None; successors.len()],
+        low_links: vec![0; successors.len()],
+        stack: Vec::new(),
+        on_stack: vec![false; successors.len()],
+        components: Vec::new(),
+    };
+    for node in 0..successors.len() {
+        if tarjan.indices[node].is_none() {
+            tarjan.visit(node);
+        }
+    }
+
+    let mut components = tarjan.components;
+    for component in &mut components {
+        component.sort_unstable();
+    }
+    components
+}
+
+struct Tarjan<'a> {
+    successors: &'a [Vec<usize>],
+    index: usize,
+    indices: Vec<Option<usize>>,
+    low_links: Vec<usize>,
+    stack: Vec<usize>,
+    on_stack: Vec<bool>,
+    components: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, node: usize) {
+        self.indices[node] = Some(self.index);
+        self.low_links[node] = self.index;
+        self.index += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        for &successor in &self.successors[node] {
+            match self.indices[successor] {
+                None => {
+                    self.visit(successor);
+                    self.low_links[node] = self.low_links[node].min(self.low_links[successor]);
+                }
+                Some(index) if self.on_stack[successor] => {
+                    self.low_links[node] = self.low_links[node].min(index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(self.low_links[node]) == self.indices[node] {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::{
+        ecs::world::World,
+        render::{
+            render_graph::{Node, ResourceSlots},
+            renderer::RenderContext,
+        },
+    };
+
+    struct EmptyNode;
+
+    impl Node for EmptyNode {
+        fn update(
+            &mut self,
+            _world: &World,
+            _render_context: &mut dyn RenderContext,
+            _input: &ResourceSlots,
+            _output: &mut ResourceSlots,
+        ) {
+        }
+    }
+
+    #[test]
+    fn acyclic_graph_has_single_node_components() {
+        let components = strongly_connected_components(&[vec![1, 2], vec![2], vec![]]);
+        assert_eq!(components, vec![vec![2], vec![1], vec![0]]);
+    }
+
+    #[test]
+    fn components_contain_the_whole_cycle() {
+        // 0 -> 1 -> 2 -> 0, 2 -> 3, 3 -> 4 -> 3
+        let successors = [vec![1], vec![2], vec![0, 3], vec![4], vec![3]];
+        let components = strongly_connected_components(&successors);
+        assert_eq!(components, vec![vec![3, 4], vec![0, 1, 2]]);
+    }
+
+    #[test]
+    fn find_cycles_reports_cycles_and_self_loops() {
+        let mut graph = RenderGraph::default();
+        for name in ["a", "b", "c", "d"].iter() {
+            graph.add_node(*name, EmptyNode);
+        }
+        graph.add_node_edge("a", "b").unwrap();
+        graph.add_node_edge("b", "c").unwrap();
+        graph.add_node_edge("c", "b").unwrap();
+        graph.add_node_edge("d", "d").unwrap();
+
+        let cycles: Vec<(Vec<&str>, usize)> = find_cycles(&graph)
+            .iter()
+            .map(|cycle| {
+                let mut names: Vec<&str> = cycle
+                    .nodes
+                    .iter()
+                    .map(|&id| graph.get_node_state(id).unwrap().name.as_deref().unwrap())
+                    .collect();
+                names.sort_unstable();
+                (names, cycle.edges.len())
+            })
+            .collect();
+
+        assert_eq!(cycles, vec![(vec!["b", "c"], 2), (vec!["d"], 1)]);
+    }
+
+    #[test]
+    fn find_cycles_ignores_acyclic_graphs() {
+        let mut graph = RenderGraph::default();
+        graph.add_node("a", EmptyNode);
+        graph.add_node("b", EmptyNode);
+        graph.add_node_edge("a", "b").unwrap();
+        assert!(find_cycles(&graph).is_empty());
+    }
+}
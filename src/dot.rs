@@ -1,5 +1,10 @@
-pub struct DotGraph {
-    buffer: String,
+use std::io::{self, Write};
+
+/// Statements are written to `W` as soon as they are added.
+/// The first io error is kept and returned from [`DotGraph::finish_writer`].
+pub struct DotGraph<W: Write = Vec<u8>> {
+    writer: W,
+    error: Option<io::Error>,
 }
 
 fn format_attributes(attrs: &[(&str, &str)]) -> String {
@@ -27,20 +32,75 @@ pub fn html_escape(input: &str) -> String {
         .replace('>', "&gt;")
 }
 
+pub fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('"', "\\\"").replace('\n', "\\n"))
+}
+
 impl DotGraph {
-    pub fn new(name: &str, options: &[(&str, &str)]) -> DotGraph {
+    /// name needs to start with `cluster` for graphviz to draw a box around the subgraph
+    pub fn subgraph(name: &str, options: &[(&str, &str)]) -> DotGraph {
+        DotGraph::with_kind(Vec::new(), "subgraph", name, options)
+    }
+
+    /// Statements which aren't wrapped into a graph, to be written into one by the caller.
+    pub fn statements() -> DotGraph {
+        DotGraph {
+            writer: Vec::new(),
+            error: None,
+        }
+    }
+
+    pub fn finish_statements(self) -> String {
+        String::from_utf8(self.writer).expect("dot output is valid utf8")
+    }
+
+    pub fn finish(self) -> String {
+        let buffer = self
+            .finish_writer()
+            .expect("writing into a Vec<u8> cannot fail");
+        String::from_utf8(buffer).expect("dot output is valid utf8")
+    }
+}
+
+impl<W: Write> DotGraph<W> {
+    pub fn from_writer(writer: W, name: &str, options: &[(&str, &str)]) -> DotGraph<W> {
+        DotGraph::with_kind(writer, "digraph", name, options)
+    }
+
+    fn with_kind(writer: W, kind: &str, name: &str, options: &[(&str, &str)]) -> DotGraph<W> {
         let mut dot = DotGraph {
-            buffer: String::new(),
+            writer,
+            error: None,
         };
 
-        dot.write(format!("digraph {} {{", name));
+        dot.write(format!("{} {} {{", kind, name));
         for (key, val) in options {
-            dot.write(format!("\t{} = {};", key, val));
+            dot.graph_attribute(key, val);
         }
 
         dot
     }
 
+    pub fn graph_attribute(&mut self, key: &str, val: &str) -> &mut Self {
+        self.write(format!("\t{} = {};", key, val));
+        self
+    }
+
+    /// `// text`, line breaks in the text are replaced with spaces
+    pub fn add_comment(&mut self, text: &str) {
+        self.write(format!("\t// {}", text.replace('\n', " ")));
+    }
+
+    /// statements from [`DotGraph::finish_statements`], written as they are
+    pub fn add_statements(&mut self, statements: &str) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(error) = self.writer.write_all(statements.as_bytes()) {
+            self.error = Some(error);
+        }
+    }
+
     pub fn edge_attributes(&mut self, attrs: &[(&str, &str)]) -> &mut Self {
         self.write(format!("\tedge {};", format_attributes(attrs)));
         self
@@ -51,9 +111,26 @@ impl DotGraph {
         self
     }
 
-    pub fn finish(mut self) -> String {
+    pub fn finish_writer(mut self) -> io::Result<W> {
         self.write("}");
-        self.buffer
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.writer),
+        }
+    }
+
+    pub fn add_sub_graph(&mut self, graph: DotGraph) {
+        for line in graph.finish().lines() {
+            self.write(format!("\t{}", line));
+        }
+    }
+
+    pub fn same_rank(&mut self, ids: impl IntoIterator<Item = impl AsRef<str>>) {
+        let ids: Vec<_> = ids
+            .into_iter()
+            .map(|id| format!("{};", id.as_ref()))
+            .collect();
+        self.write(format!("\t{{ rank = same; {} }}", ids.join(" ")));
     }
 
     /// label needs to include the quotes
@@ -83,7 +160,11 @@ impl DotGraph {
     }
 
     fn write(&mut self, text: impl AsRef<str>) {
-        self.buffer.push_str(text.as_ref());
-        self.buffer.push('\n');
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(error) = writeln!(self.writer, "{}", text.as_ref()) {
+            self.error = Some(error);
+        }
     }
 }
@@ -0,0 +1,1179 @@
+use crate::{
+    dot::{font_tag, html_escape, quote, DotGraph},
+    graph::{
+        ClusterKind, DebugCluster, DebugEdge, DebugGraph, DebugNode, DebugSlot, EdgeKind,
+        GraphExporter, NodeCategory, NodeKind,
+    },
+    utils,
+};
+use itertools::{EitherOrBoth, Itertools};
+use std::{
+    fmt,
+    io::{self, Write},
+    sync::Arc,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verbosity {
+    /// node names and slot names
+    Minimal,
+    /// additionally short type names and slot resource types
+    Normal,
+    /// additionally full type paths, node uuids and slot indices
+    Verbose,
+}
+
+impl Default for Verbosity {
+    fn default() -> Self {
+        Verbosity::Normal
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DotSettings {
+    pub verbosity: Verbosity,
+    /// Links every node to the docs.rs page of its type, using the given crate version (e.g. `"latest"`).
+    /// Only has an effect on output formats supporting links, like svg.
+    pub docs_rs_version: Option<String>,
+    /// Fills render nodes with a color depending on their [`NodeCategory`] and adds a legend.
+    pub color_categories: bool,
+    pub fonts: FontSettings,
+    pub palette: Palette,
+    /// Limits the width of node titles, type names and slots to this many characters.
+    /// The full text is still shown in the tooltip.
+    pub max_label_width: Option<usize>,
+    pub label_overflow: LabelOverflow,
+    /// Writes the slot names and resource types on the slot edges instead of into rows of the render nodes,
+    /// and connects the nodes without ports. Works better with `splines=ortho`.
+    pub slot_edge_labels: bool,
+    /// Connects edges to the centers of the nodes instead of the ports of their slots or titles,
+    /// as the ports often make graphviz tangle the edges of large graphs. The slots are still listed in the nodes.
+    pub no_ports: bool,
+    /// Order of the slot rows within render nodes.
+    pub slot_order: SlotOrder,
+    /// Puts every slot into its own row, inputs left aligned above the right aligned outputs,
+    /// instead of pairing the n-th input with the n-th output in a row, which looks like they are connected.
+    pub separate_slot_rows: bool,
+    /// Layout engine and graph attributes, for graphs too large for the default layout.
+    pub layout: LayoutSettings,
+    /// Replaces the built-in labels of all nodes except sync points, e.g. to add project specific metadata.
+    /// The formatted labels have no ports, so edges connect to the centers of the nodes like with [`DotSettings::no_ports`].
+    pub label_formatter: Option<LabelFormatter>,
+    /// Writes a comment like `// node: id=main_pass name=main_pass type=bevy_render::pass::PassNode<..> kind=render_node`
+    /// before every node and edge, so scripts can post-process the output without parsing dot.
+    /// Values containing whitespace, quotes or `=` are quoted.
+    pub metadata_comments: bool,
+}
+
+/// Builds the html label of a node, see [`DotSettings::label_formatter`].
+#[derive(Clone)]
+pub struct LabelFormatter(Arc<dyn Fn(&DebugNode) -> String + Send + Sync>);
+
+impl LabelFormatter {
+    /// `format` returns graphviz html, without the surrounding `<` and `>`.
+    /// Text taken from the node needs to be escaped with [`html_escape`](crate::html_escape).
+    pub fn new(format: impl Fn(&DebugNode) -> String + Send + Sync + 'static) -> LabelFormatter {
+        LabelFormatter(Arc::new(format))
+    }
+
+    /// Graphviz html in which `{name}`, `{type_name}`, `{inputs}`, `{outputs}` and `{details}` are replaced
+    /// with the escaped values of the node. Slots and details are separated by line breaks.
+    ///
+    /// e.g. `"<B>{name}</B><BR/>{type_name}"`
+    pub fn template(template: impl Into<String>) -> LabelFormatter {
+        let template = template.into();
+        LabelFormatter::new(move |node| {
+            let slots = |slots: &[DebugSlot]| {
+                slots
+                    .iter()
+                    .map(|slot| html_escape(&format!("{}: {}", slot.name, slot.resource_type)))
+                    .collect::<Vec<_>>()
+                    .join("<BR/>")
+            };
+            let details: Vec<_> = node
+                .details
+                .iter()
+                .map(|detail| html_escape(detail))
+                .collect();
+            template
+                .replace("{name}", &html_escape(&node.name))
+                .replace("{type_name}", &html_escape(&node.type_name))
+                .replace("{inputs}", &slots(&node.inputs))
+                .replace("{outputs}", &slots(&node.outputs))
+                .replace("{details}", &details.join("<BR/>"))
+        })
+    }
+
+    fn format(&self, node: &DebugNode) -> String {
+        format!("<{}>", (self.0)(node))
+    }
+}
+
+impl fmt::Debug for LabelFormatter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LabelFormatter")
+    }
+}
+
+/// What happens to labels longer than [`DotSettings::max_label_width`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelOverflow {
+    /// cut off, ending with `…`
+    Ellipsis,
+    /// continued on the next lines
+    Wrap,
+}
+
+impl Default for LabelOverflow {
+    fn default() -> Self {
+        LabelOverflow::Ellipsis
+    }
+}
+
+/// Order of the slot rows, see [`DotSettings::slot_order`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotOrder {
+    /// in the order the node declares them
+    Index,
+    /// in the order the node declares them, prefixed with their index like in [`Verbosity::Verbose`]
+    IndexLabeled,
+    /// by name, inputs and outputs sorted separately
+    Alphabetical,
+}
+
+impl Default for SlotOrder {
+    fn default() -> Self {
+        SlotOrder::Index
+    }
+}
+
+/// Graphviz layout engines, selected with the `layout` graph attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutEngine {
+    /// hierarchical layout in execution order, the only engine using ranks and edges between clusters
+    Dot,
+    /// spring model, for graphs of up to a few hundred nodes
+    Neato,
+    /// force directed, keeping clusters together
+    Fdp,
+    /// force directed, scaling to very large graphs
+    Sfdp,
+    Circo,
+    Twopi,
+}
+
+impl Default for LayoutEngine {
+    fn default() -> Self {
+        LayoutEngine::Dot
+    }
+}
+
+impl LayoutEngine {
+    pub fn name(self) -> &'static str {
+        match self {
+            LayoutEngine::Dot => "dot",
+            LayoutEngine::Neato => "neato",
+            LayoutEngine::Fdp => "fdp",
+            LayoutEngine::Sfdp => "sfdp",
+            LayoutEngine::Circo => "circo",
+            LayoutEngine::Twopi => "twopi",
+        }
+    }
+}
+
+/// Hints for laying out graphs with hundreds of nodes, which the default dot layout makes unusably wide.
+#[derive(Debug, Clone, Default)]
+pub struct LayoutSettings {
+    pub engine: LayoutEngine,
+    /// `overlap=false`, moves overlapping nodes apart with the force directed engines
+    pub remove_overlap: bool,
+    /// `concentrate=true`, merges parallel edges into shared segments
+    pub concentrate: bool,
+    /// additional graph attributes like `("splines", "ortho")`, the values are quoted
+    pub attributes: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FontSettings {
+    /// Graphviz falls back to its default font if the family isn't installed.
+    pub family: String,
+    /// size of node titles and system names
+    pub title_size: u8,
+    pub title_color: String,
+    /// size of the type names below the titles and of edge labels
+    pub detail_size: u8,
+    pub detail_color: String,
+}
+
+impl Default for FontSettings {
+    fn default() -> Self {
+        FontSettings {
+            family: "Roboto".to_string(),
+            title_size: 14,
+            title_color: "black".to_string(),
+            detail_size: 10,
+            detail_color: "red".to_string(),
+        }
+    }
+}
+
+/// Colors of the edges and nodes carrying a meaning, in any format graphviz understands (e.g. `"#0072b2"` or `"blue"`).
+/// The default uses the usual red and green conventions, see [`Palette::color_blind`] for an alternative.
+#[derive(Debug, Clone)]
+pub struct Palette {
+    /// edges in a cycle or connecting slots of different types
+    pub highlight: String,
+    pub slot_edge: String,
+    pub chain: String,
+    pub conflict: String,
+    pub read_after_write: String,
+    pub exclusive_fill: String,
+    pub exclusive_border: String,
+    /// nodes and edges only shown as context
+    pub dimmed: String,
+    /// backgrounds of the [`NodeCategory`]s, with [`DotSettings::color_categories`]
+    pub pass: String,
+    pub texture: String,
+    pub camera: String,
+    pub resources: String,
+    pub sub_graph: String,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette {
+            highlight: "red".to_string(),
+            slot_edge: "blue".to_string(),
+            chain: "darkgreen".to_string(),
+            conflict: "red".to_string(),
+            read_after_write: "purple".to_string(),
+            exclusive_fill: "#ffe4cc".to_string(),
+            exclusive_border: "darkorange".to_string(),
+            dimmed: "#c8c8c8".to_string(),
+            pass: "#cfe2ff".to_string(),
+            texture: "#d1e7dd".to_string(),
+            camera: "#fff3cd".to_string(),
+            resources: "#e2d9f3".to_string(),
+            sub_graph: "#f8d7da".to_string(),
+        }
+    }
+}
+
+impl Palette {
+    /// Based on the Okabe-Ito palette, which stays distinguishable with the common color vision deficiencies.
+    pub fn color_blind() -> Palette {
+        Palette {
+            highlight: "#d55e00".to_string(),
+            slot_edge: "#0072b2".to_string(),
+            chain: "#009e73".to_string(),
+            conflict: "#d55e00".to_string(),
+            read_after_write: "#cc79a7".to_string(),
+            exclusive_fill: "#fbe3b3".to_string(),
+            exclusive_border: "#e69f00".to_string(),
+            dimmed: "#c8c8c8".to_string(),
+            pass: "#cce6f6".to_string(),
+            texture: "#fbf7c6".to_string(),
+            camera: "#f8d9c0".to_string(),
+            resources: "#f3dcea".to_string(),
+            sub_graph: "#c6e9df".to_string(),
+        }
+    }
+
+    fn category_color(&self, category: NodeCategory) -> Option<&str> {
+        match category {
+            NodeCategory::Pass => Some(&self.pass),
+            NodeCategory::Texture => Some(&self.texture),
+            NodeCategory::Camera => Some(&self.camera),
+            NodeCategory::Resources => Some(&self.resources),
+            NodeCategory::SubGraph => Some(&self.sub_graph),
+            NodeCategory::Other => None,
+        }
+    }
+}
+
+/// Exports a [`DebugGraph`] in the graphviz dot format.
+pub struct DotExporter<'a> {
+    settings: &'a DotSettings,
+}
+
+impl<'a> DotExporter<'a> {
+    pub fn new(settings: &'a DotSettings) -> DotExporter<'a> {
+        DotExporter { settings }
+    }
+}
+
+fn cluster_id(id: &str) -> String {
+    quote(&format!("cluster_{}", id))
+}
+
+/// invisible node inside of every cluster, to connect clusters with edges
+fn marker_id(id: &str) -> String {
+    quote(&format!("{}_marker", id))
+}
+
+impl GraphExporter for DotExporter<'_> {
+    fn export(&self, graph: &DebugGraph, writer: &mut dyn Write) -> io::Result<()> {
+        let mut dot = DotGraph::from_writer(writer, &quote(&graph.name), &[]);
+        for statement in self.statements(graph) {
+            dot.add_statements(&statement);
+        }
+        dot.finish_writer().map(drop)
+    }
+}
+
+/// Position of the next statement generated by [`DotExporter::next_statement`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum StatementCursor {
+    Attributes,
+    Cluster(usize),
+    Node(usize),
+    Rank(usize),
+    Legend,
+    Edge(usize),
+    Done,
+}
+
+impl Default for StatementCursor {
+    fn default() -> Self {
+        StatementCursor::Attributes
+    }
+}
+
+impl DotExporter<'_> {
+    /// The statements of the exported graph, generated one at a time when they are requested.
+    /// Written between `digraph name {` and `}` they give the same output as [`GraphExporter::export`],
+    /// so other statements, like annotations or custom clusters, can be placed in between.
+    ///
+    /// Every item ends with a newline, top level clusters and the legend are a single item.
+    pub fn statements<'b>(&'b self, graph: &'b DebugGraph) -> impl Iterator<Item = String> + 'b {
+        let mut cursor = StatementCursor::default();
+        std::iter::from_fn(move || self.next_statement(graph, &mut cursor))
+    }
+
+    pub(crate) fn next_statement(
+        &self,
+        graph: &DebugGraph,
+        cursor: &mut StatementCursor,
+    ) -> Option<String> {
+        let mut dot = DotGraph::statements();
+        loop {
+            match *cursor {
+                StatementCursor::Attributes => {
+                    self.add_attributes(&mut dot, graph);
+                    *cursor = StatementCursor::Cluster(0);
+                }
+                StatementCursor::Cluster(index) => match graph.clusters.get(index) {
+                    Some(cluster) => {
+                        *cursor = StatementCursor::Cluster(index + 1);
+                        if cluster.parent.is_some() {
+                            continue;
+                        }
+                        dot.add_sub_graph(self.cluster_subgraph(graph, cluster));
+                    }
+                    None => {
+                        *cursor = StatementCursor::Node(0);
+                        continue;
+                    }
+                },
+                StatementCursor::Node(index) => match graph.nodes.get(index) {
+                    Some(node) => {
+                        *cursor = StatementCursor::Node(index + 1);
+                        if node.cluster.is_some() {
+                            continue;
+                        }
+                        self.add_node(&mut dot, node);
+                    }
+                    None => {
+                        *cursor = StatementCursor::Rank(0);
+                        continue;
+                    }
+                },
+                StatementCursor::Rank(index) => match graph.ranks.get(index) {
+                    Some(rank) => {
+                        *cursor = StatementCursor::Rank(index + 1);
+                        // ranks inside of clusters are part of the cluster
+                        let top_level = rank.first().and_then(|id| graph.node(id));
+                        if !matches!(top_level, Some(node) if node.cluster.is_none()) {
+                            continue;
+                        }
+                        dot.same_rank(rank.iter().map(|id| quote(id)));
+                    }
+                    None => {
+                        *cursor = StatementCursor::Legend;
+                        continue;
+                    }
+                },
+                StatementCursor::Legend => {
+                    *cursor = StatementCursor::Edge(0);
+                    if !self.settings.color_categories {
+                        continue;
+                    }
+                    add_legend(&mut dot, graph, &self.settings.palette);
+                }
+                StatementCursor::Edge(index) => match graph.edges.get(index) {
+                    Some(edge) => {
+                        *cursor = StatementCursor::Edge(index + 1);
+                        self.add_edge(&mut dot, graph, edge);
+                    }
+                    None => {
+                        *cursor = StatementCursor::Done;
+                        continue;
+                    }
+                },
+                StatementCursor::Done => return None,
+            }
+            return Some(dot.finish_statements());
+        }
+    }
+
+    fn add_attributes<W: Write>(&self, dot: &mut DotGraph<W>, graph: &DebugGraph) {
+        if !graph.clusters.is_empty() {
+            // allows edges between clusters using `lhead` and `ltail`
+            dot.graph_attribute("compound", "true");
+        }
+        dot.graph_attribute("rankdir", "LR")
+            .graph_attribute("ranksep", "1.0");
+
+        let layout = &self.settings.layout;
+        if layout.engine != LayoutEngine::Dot {
+            dot.graph_attribute("layout", layout.engine.name());
+        }
+        if layout.remove_overlap {
+            dot.graph_attribute("overlap", "false");
+        }
+        if layout.concentrate {
+            dot.graph_attribute("concentrate", "true");
+        }
+        for (key, value) in &layout.attributes {
+            dot.graph_attribute(key, &quote(value));
+        }
+
+        let fonts = &self.settings.fonts;
+        let family = quote(&fonts.family);
+        let title_size = fonts.title_size.to_string();
+        let title_color = quote(&fonts.title_color);
+        dot.edge_attributes(&[("fontname", &family)])
+            .node_attributes(&[
+                ("fontname", &family),
+                ("fontsize", &title_size),
+                ("fontcolor", &title_color),
+            ]);
+    }
+
+    fn add_contents<W: Write>(
+        &self,
+        dot: &mut DotGraph<W>,
+        graph: &DebugGraph,
+        cluster: Option<&str>,
+    ) {
+        for child in graph.child_clusters(cluster) {
+            dot.add_sub_graph(self.cluster_subgraph(graph, child));
+        }
+
+        for node in graph.nodes_in(cluster) {
+            self.add_node(dot, node);
+        }
+
+        let in_cluster =
+            |id: &String| graph.node(id).map(|node| node.cluster.as_deref()) == Some(cluster);
+        for rank in &graph.ranks {
+            if matches!(rank.first(), Some(id) if in_cluster(id)) {
+                dot.same_rank(rank.iter().map(|id| quote(id)));
+            }
+        }
+    }
+
+    fn cluster_subgraph(&self, graph: &DebugGraph, cluster: &DebugCluster) -> DotGraph {
+        let label = match cluster.fixed_timestep {
+            Some(step) => format!("{}\nfixed timestep: {}s", cluster.label, step),
+            None => cluster.label.clone(),
+        };
+        let label = quote(&label);
+        let mut options = vec![("label", label.as_str())];
+        match cluster.kind {
+            ClusterKind::Stage | ClusterKind::Schedule => {
+                options.push(("fontsize", "20"));
+                // dashed borders set nested and fixed timestep loops apart from the frame loop
+                if cluster.kind == ClusterKind::Schedule || cluster.fixed_timestep.is_some() {
+                    options.push(("style", "\"rounded,dashed\""));
+                } else {
+                    options.push(("style", "rounded"));
+                }
+            }
+            ClusterKind::SystemSet => options.push(("style", "dotted")),
+            ClusterKind::AmbiguitySet => options.extend(&[
+                ("style", "\"rounded,filled\""),
+                ("fillcolor", "\"#f0f0f0\""),
+                ("color", "\"#d8d8d8\""),
+            ]),
+        }
+
+        let mut subgraph = DotGraph::subgraph(&cluster_id(&cluster.id), &options);
+
+        let is_connected = graph.edges.iter().any(|edge| {
+            edge.kind == EdgeKind::ClusterOrder
+                && (edge.from == cluster.id || edge.to == cluster.id)
+        });
+        if is_connected {
+            subgraph.add_node(
+                &marker_id(&cluster.id),
+                &[("shape", "point"), ("style", "invis"), ("label", "\"\"")],
+            );
+        }
+
+        self.add_contents(&mut subgraph, graph, Some(&cluster.id));
+        subgraph
+    }
+
+    fn builtin_label(&self, node: &DebugNode) -> String {
+        let verbosity = self.settings.verbosity;
+
+        match node.kind {
+            NodeKind::RenderNode => self.render_node_label(node),
+            NodeKind::System | NodeKind::ExclusiveSystem if !node.access.is_empty() => {
+                self.system_access_label(node)
+            }
+            NodeKind::System | NodeKind::ExclusiveSystem => match verbosity {
+                Verbosity::Verbose => quote(&fit_label(&node.type_name, self.settings)),
+                Verbosity::Minimal | Verbosity::Normal => {
+                    quote(&fit_label(&node.name, self.settings))
+                }
+            },
+            NodeKind::Placeholder | NodeKind::Plugin | NodeKind::PluginGroup => {
+                quote(&fit_label(&node.name, self.settings))
+            }
+            // the name is placed next to the bar instead
+            NodeKind::SyncPoint => "\"\"".to_string(),
+        }
+    }
+
+    fn add_node<W: Write>(&self, dot: &mut DotGraph<W>, node: &DebugNode) {
+        if self.settings.metadata_comments {
+            dot.add_comment(&node_metadata(node));
+        }
+        if node.dimmed {
+            let label = quote(&fit_label(&node.name, self.settings));
+            let color = quote(&self.settings.palette.dimmed);
+            dot.add_node(
+                &quote(&node.id),
+                &[
+                    ("label", &label),
+                    ("shape", "box"),
+                    ("color", &color),
+                    ("fontcolor", &color),
+                ],
+            );
+            return;
+        }
+        let formatter = match &self.settings.label_formatter {
+            Some(formatter) if node.kind != NodeKind::SyncPoint => Some(formatter),
+            _ => None,
+        };
+        let label = match formatter {
+            Some(formatter) => formatter.format(node),
+            None => self.builtin_label(node),
+        };
+        let shape = match node.kind {
+            _ if formatter.is_some() => "box",
+            NodeKind::RenderNode | NodeKind::Placeholder => "plaintext",
+            // the table draws the border itself
+            NodeKind::System | NodeKind::ExclusiveSystem if !node.access.is_empty() => "plaintext",
+            NodeKind::System | NodeKind::ExclusiveSystem | NodeKind::SyncPoint => "box",
+            NodeKind::Plugin => "component",
+            NodeKind::PluginGroup => "folder",
+        };
+
+        let url = match (&self.settings.docs_rs_version, &node.kind) {
+            (Some(version), NodeKind::RenderNode) => {
+                utils::docs_rs_url(&node.type_name, version).map(|url| quote(&url))
+            }
+            _ => None,
+        };
+
+        let tooltip = quote(&tooltip(node, self.settings));
+
+        let mut attrs = vec![
+            ("label", label.as_str()),
+            ("shape", shape),
+            ("tooltip", tooltip.as_str()),
+        ];
+        if let Some(url) = &url {
+            attrs.extend(&[("URL", url.as_str()), ("target", "_blank")]);
+        }
+        let sync_label = quote(&node.name);
+        let detail_size = self.settings.fonts.detail_size.to_string();
+        let palette = &self.settings.palette;
+        let exclusive_fill = quote(&palette.exclusive_fill);
+        let exclusive_border = quote(&palette.exclusive_border);
+        // exclusive systems run with nothing else in parallel, so they stand out as the bottlenecks of the frame
+        if node.kind == NodeKind::ExclusiveSystem && node.access.is_empty() {
+            attrs.extend(&[
+                ("style", "filled"),
+                ("fillcolor", exclusive_fill.as_str()),
+                ("color", exclusive_border.as_str()),
+                ("penwidth", "2"),
+                ("peripheries", "2"),
+            ]);
+        }
+        if node.kind == NodeKind::SyncPoint {
+            attrs.extend(&[
+                ("style", "filled"),
+                ("fillcolor", "gray"),
+                ("width", "0.1"),
+                ("height", "0.8"),
+                ("xlabel", sync_label.as_str()),
+                ("fontsize", detail_size.as_str()),
+            ]);
+        }
+        dot.add_node(&quote(&node.id), &attrs);
+    }
+
+    fn render_node_label(&self, node: &DebugNode) -> String {
+        let slots = if self.settings.slot_edge_labels {
+            String::new()
+        } else {
+            self.slot_rows(node)
+        };
+
+        let background = (self.settings.palette)
+            .category_color(node.category())
+            .filter(|_| self.settings.color_categories)
+            .map(|color| format!(" BGCOLOR=\"{}\"", color))
+            .unwrap_or_default();
+
+        format!(
+            "<<TABLE STYLE=\"rounded\"{}><TR><TD PORT=\"title\" BORDER=\"0\" COLSPAN=\"2\">{}{}</TD></TR>{}</TABLE>>",
+            background,
+            html_lines(&fit_label(&node.name, self.settings)),
+            title_details(node, self.settings),
+            slots,
+        )
+    }
+
+    fn slot_rows(&self, node: &DebugNode) -> String {
+        let (input_align, output_align) = if self.settings.separate_slot_rows {
+            (Some("LEFT"), Some("RIGHT"))
+        } else {
+            (None, None)
+        };
+        let inputs = self
+            .ordered_slots(&node.inputs)
+            .map(|(index, slot)| slot_cell(index, slot, input_align, self.settings))
+            .collect::<Vec<_>>();
+
+        let outputs = self
+            .ordered_slots(&node.outputs)
+            .map(|(index, slot)| slot_cell(index, slot, output_align, self.settings))
+            .collect::<Vec<_>>();
+
+        let empty = "<TD BORDER=\"0\">&nbsp;</TD>";
+        if self.settings.separate_slot_rows {
+            let inputs = inputs
+                .iter()
+                .map(|input| format!("<TR>{}{}</TR>", input, empty));
+            let outputs = outputs
+                .iter()
+                .map(|output| format!("<TR>{}{}</TR>", empty, output));
+            return inputs.chain(outputs).collect();
+        }
+
+        inputs
+            .iter()
+            .zip_longest(outputs.iter())
+            .map(|pair| match pair {
+                EitherOrBoth::Both(input, output) => format!("<TR>{}{}</TR>", input, output),
+                EitherOrBoth::Left(input) => format!("<TR>{}{}</TR>", input, empty),
+                EitherOrBoth::Right(output) => format!("<TR>{}{}</TR>", empty, output),
+            })
+            .collect::<String>()
+    }
+
+    /// the slots with their index, which is their port, in the [`DotSettings::slot_order`]
+    fn ordered_slots<'s>(
+        &self,
+        slots: &'s [DebugSlot],
+    ) -> impl Iterator<Item = (usize, &'s DebugSlot)> {
+        let mut slots: Vec<_> = slots.iter().enumerate().collect();
+        if self.settings.slot_order == SlotOrder::Alphabetical {
+            slots.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name));
+        }
+        slots.into_iter()
+    }
+
+    /// system name with a row per accessed component, mutable access in bold
+    fn system_access_label(&self, node: &DebugNode) -> String {
+        let name = match self.settings.verbosity {
+            Verbosity::Verbose => &node.type_name,
+            Verbosity::Minimal | Verbosity::Normal => &node.name,
+        };
+        let rows: String = node
+            .access
+            .iter()
+            .map(|access| {
+                let text = html_lines(&fit_label(&access.component, self.settings));
+                if access.mutable {
+                    format!("<TR><TD ALIGN=\"LEFT\"><B>&amp;mut {}</B></TD></TR>", text)
+                } else {
+                    format!("<TR><TD ALIGN=\"LEFT\">&amp;{}</TD></TR>", text)
+                }
+            })
+            .collect();
+        let palette = &self.settings.palette;
+        let border = if node.kind == NodeKind::ExclusiveSystem {
+            format!(
+                "BORDER=\"3\" COLOR=\"{}\" BGCOLOR=\"{}\"",
+                palette.exclusive_border, palette.exclusive_fill
+            )
+        } else {
+            "BORDER=\"1\"".to_string()
+        };
+        format!(
+            "<<TABLE {} CELLBORDER=\"0\" CELLSPACING=\"0\"><TR><TD>{}</TD></TR>{}</TABLE>>",
+            border,
+            html_lines(&fit_label(name, self.settings)),
+            rows
+        )
+    }
+
+    /// e.g. `color -> color_attachment` and the resource type, or both types if they differ
+    fn slot_edge_label(
+        &self,
+        graph: &DebugGraph,
+        edge: &DebugEdge,
+        output_index: usize,
+        input_index: usize,
+    ) -> String {
+        let output = graph.node(&edge.from).and_then(|node| node.outputs.get(output_index));
+        let input = graph.node(&edge.to).and_then(|node| node.inputs.get(input_index));
+        let (output, input) = match (output, input) {
+            (Some(output), Some(input)) => (output, input),
+            _ => return format!("#{} -> #{}", output_index, input_index),
+        };
+
+        match self.settings.verbosity {
+            Verbosity::Minimal => format!("{} -> {}", output.name, input.name),
+            Verbosity::Normal | Verbosity::Verbose
+                if output.resource_type == input.resource_type =>
+            {
+                format!(
+                    "{} -> {}\n{}",
+                    output.name, input.name, output.resource_type
+                )
+            }
+            Verbosity::Normal | Verbosity::Verbose => format!(
+                "{}: {} -> {}: {}",
+                output.name, output.resource_type, input.name, input.resource_type
+            ),
+        }
+    }
+
+    fn add_edge<W: Write>(&self, dot: &mut DotGraph<W>, graph: &DebugGraph, edge: &DebugEdge) {
+        let from = quote(&edge.from);
+        let to = quote(&edge.to);
+        let label = edge.label.as_deref().map(quote);
+        let palette = &self.settings.palette;
+        let highlight_color = quote(&palette.highlight);
+        let highlight = [("color", highlight_color.as_str()), ("style", "bold")];
+        let slot_color = quote(&palette.slot_edge);
+        let chain_color = quote(&palette.chain);
+        let conflict_color = quote(&palette.conflict);
+        let read_after_write_color = quote(&palette.read_after_write);
+        let detail_size = self.settings.fonts.detail_size.to_string();
+
+        if self.settings.metadata_comments && edge.kind != EdgeKind::ClusterOrder {
+            dot.add_comment(&edge_metadata(graph, edge));
+        }
+        if edge.dimmed && edge.kind != EdgeKind::ClusterOrder {
+            let dimmed = quote(&palette.dimmed);
+            let mut attrs = vec![
+                ("color", dimmed.as_str()),
+                ("fontcolor", &dimmed),
+                ("fontsize", &detail_size),
+            ];
+            if let Some(label) = &label {
+                attrs.push(("label", label));
+            }
+            if !edge.kind.is_directed() {
+                attrs.extend(&[("dir", "none"), ("constraint", "false")]);
+            }
+            dot.add_edge(&from, None, &to, None, &attrs);
+            return;
+        }
+
+        // ports get in the way of orthogonal splines, which the edge labels are meant for
+        let no_ports = self.settings.no_ports || self.settings.label_formatter.is_some();
+        let (title_out, title_in) = if self.settings.slot_edge_labels || no_ports {
+            (None, None)
+        } else {
+            (Some("title:e"), Some("title:w"))
+        };
+
+        match &edge.kind {
+            EdgeKind::Slot {
+                output_index,
+                input_index,
+            } if self.settings.slot_edge_labels => {
+                let slot_label =
+                    quote(&self.slot_edge_label(graph, edge, *output_index, *input_index));
+                let mut attrs = vec![
+                    ("label", slot_label.as_str()),
+                    ("fontsize", detail_size.as_str()),
+                ];
+                if edge.highlighted {
+                    attrs.extend(&highlight);
+                } else {
+                    attrs.extend(&[("color", slot_color.as_str()), ("fontcolor", &slot_color)]);
+                }
+                dot.add_edge(&from, None, &to, None, &attrs);
+            }
+            EdgeKind::Slot {
+                output_index,
+                input_index,
+            } => {
+                let (output_port, input_port) = if no_ports {
+                    (None, None)
+                } else {
+                    (
+                        Some(format!("{}:e", output_index)),
+                        Some(format!("{}:w", input_index)),
+                    )
+                };
+                let slot_attrs = [("color", slot_color.as_str())];
+                dot.add_edge(
+                    &from,
+                    output_port.as_deref(),
+                    &to,
+                    input_port.as_deref(),
+                    if edge.highlighted {
+                        &highlight
+                    } else {
+                        &slot_attrs
+                    },
+                );
+            }
+            EdgeKind::Slots(_) => {
+                let mut attrs = vec![("fontsize", detail_size.as_str())];
+                if let Some(label) = &label {
+                    attrs.push(("label", label));
+                }
+                if edge.highlighted {
+                    attrs.extend(&highlight);
+                } else {
+                    attrs.extend(&[("color", slot_color.as_str()), ("fontcolor", &slot_color)]);
+                }
+                dot.add_edge(&from, title_out, &to, title_in, &attrs);
+            }
+            EdgeKind::Node => {
+                dot.add_edge(
+                    &from,
+                    title_out,
+                    &to,
+                    title_in,
+                    if edge.highlighted { &highlight } else { &[] },
+                );
+            }
+            EdgeKind::Ordering => {
+                // hollow arrowheads set ordering apart from the filled ones of edges passing data
+                let mut attrs = vec![("fontsize", detail_size.as_str()), ("arrowhead", "empty")];
+                if let Some(label) = &label {
+                    attrs.push(("label", label));
+                }
+                if edge.highlighted {
+                    attrs.extend(&highlight);
+                }
+                dot.add_edge(&from, None, &to, None, &attrs);
+            }
+            EdgeKind::Chain => {
+                let mut attrs = vec![("style", "bold"), ("color", &chain_color)];
+                if let Some(label) = &label {
+                    attrs.extend(&[("label", label.as_str()), ("fontsize", &detail_size)]);
+                }
+                dot.add_edge(&from, None, &to, None, &attrs);
+            }
+            EdgeKind::Conflict => {
+                dot.add_edge(
+                    &from,
+                    None,
+                    &to,
+                    None,
+                    &[
+                        ("style", "dashed"),
+                        ("color", &conflict_color),
+                        ("dir", "none"),
+                        ("constraint", "false"),
+                    ],
+                );
+            }
+            EdgeKind::ReadAfterWrite => {
+                let mut attrs = vec![
+                    ("style", "dashed"),
+                    ("color", &read_after_write_color),
+                    ("fontcolor", &read_after_write_color),
+                    ("fontsize", detail_size.as_str()),
+                    ("constraint", "false"),
+                ];
+                if let Some(label) = &label {
+                    attrs.push(("label", label));
+                }
+                dot.add_edge(&from, None, &to, None, &attrs);
+            }
+            EdgeKind::ClusterOrder => {
+                if graph.cluster(&edge.from).is_none() || graph.cluster(&edge.to).is_none() {
+                    return;
+                }
+                dot.add_edge(
+                    &marker_id(&edge.from),
+                    None,
+                    &marker_id(&edge.to),
+                    None,
+                    &[
+                        ("ltail", &cluster_id(&edge.from)),
+                        ("lhead", &cluster_id(&edge.to)),
+                        ("style", "bold"),
+                    ],
+                );
+            }
+        }
+    }
+}
+
+/// lists the colors of the categories occurring in the graph
+fn add_legend<W: Write>(dot: &mut DotGraph<W>, graph: &DebugGraph, palette: &Palette) {
+    let mut categories: Vec<NodeCategory> = graph
+        .nodes
+        .iter()
+        .filter(|node| node.kind == NodeKind::RenderNode)
+        .map(DebugNode::category)
+        .collect();
+    categories.sort_unstable();
+    categories.dedup();
+
+    let rows: String = categories
+        .into_iter()
+        .filter_map(|category| {
+            let color = palette.category_color(category)?;
+            Some(format!(
+                "<TR><TD BGCOLOR=\"{}\">{}</TD></TR>",
+                color,
+                html_escape(category.name())
+            ))
+        })
+        .collect();
+    if rows.is_empty() {
+        return;
+    }
+
+    let mut legend = DotGraph::subgraph(&cluster_id("__legend"), &[("label", "\"Legend\"")]);
+    legend.add_node(
+        &quote("__legend"),
+        &[
+            ("label", &format!("<<TABLE BORDER=\"0\">{}</TABLE>>", rows)),
+            ("shape", "plaintext"),
+        ],
+    );
+    dot.add_sub_graph(legend);
+}
+
+/// Shortens the text to [`DotSettings::max_label_width`] characters,
+/// wrapped lines are separated by `\n`.
+fn fit_label(text: &str, settings: &DotSettings) -> String {
+    let width = match settings.max_label_width {
+        Some(width) if text.chars().count() > width => width.max(1),
+        _ => return text.to_string(),
+    };
+    match settings.label_overflow {
+        LabelOverflow::Ellipsis => {
+            let mut shortened: String = text.chars().take(width.saturating_sub(1)).collect();
+            shortened.push('…');
+            shortened
+        }
+        LabelOverflow::Wrap => {
+            let chars: Vec<char> = text.chars().collect();
+            chars
+                .chunks(width)
+                .map(|line| line.iter().collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    }
+}
+
+/// escapes the text for html labels, keeping its line breaks
+fn html_lines(text: &str) -> String {
+    html_escape(text).replace('\n', "<BR/>")
+}
+
+/// the full type path and slot types, shown when hovering over the node in svg output
+fn tooltip(node: &DebugNode, settings: &DotSettings) -> String {
+    let mut lines = vec![node.type_name.clone()];
+    if fit_label(&node.name, settings) != node.name {
+        lines.insert(0, node.name.clone());
+    }
+    for (title, slots) in &[("inputs", &node.inputs), ("outputs", &node.outputs)] {
+        if !slots.is_empty() {
+            lines.push(format!("{}:", title));
+            lines.extend(
+                slots
+                    .iter()
+                    .map(|slot| format!("  {}: {}", slot.name, slot.resource_type)),
+            );
+        }
+    }
+    lines.join("\n")
+}
+
+fn slot_cell(
+    index: usize,
+    slot: &DebugSlot,
+    align: Option<&str>,
+    settings: &DotSettings,
+) -> String {
+    let mut text = match settings.verbosity {
+        Verbosity::Minimal => slot.name.clone(),
+        Verbosity::Normal => format!("{}: {}", slot.name, slot.resource_type),
+        Verbosity::Verbose => format!("#{} {}: {}", index, slot.name, slot.resource_type),
+    };
+    if settings.slot_order == SlotOrder::IndexLabeled && settings.verbosity != Verbosity::Verbose {
+        text = format!("#{} {}", index, text);
+    }
+    let align = align
+        .map(|align| format!(" ALIGN=\"{}\"", align))
+        .unwrap_or_default();
+    let resource = match (&slot.resource, settings.verbosity) {
+        (Some(resource), verbosity) if verbosity != Verbosity::Minimal => {
+            let size = settings.fonts.detail_size;
+            let resource = font_tag(&fit_label(resource, settings), "gray", size);
+            format!("<BR/>{}", resource.replace('\n', "<BR/>"))
+        }
+        _ => String::new(),
+    };
+    format!(
+        "<TD PORT=\"{}\"{}>{}{}</TD>",
+        index,
+        align,
+        html_lines(&fit_label(&text, settings)),
+        resource
+    )
+}
+
+fn title_details(node: &DebugNode, settings: &DotSettings) -> String {
+    let size = settings.fonts.detail_size;
+    let color = &settings.fonts.detail_color;
+    // `font_tag` escapes the text, only the line breaks are left to replace
+    let type_name =
+        |name: &str| font_tag(&fit_label(name, settings), color, size).replace('\n', "<BR/>");
+    match settings.verbosity {
+        Verbosity::Minimal => String::new(),
+        Verbosity::Normal => format!("<BR/>{}", type_name(&utils::short_name(&node.type_name))),
+        Verbosity::Verbose => {
+            let details: String = node
+                .details
+                .iter()
+                .map(|detail| {
+                    let size = size.saturating_sub(2).max(1);
+                    format!("<BR/>{}", font_tag(detail, "gray", size))
+                })
+                .collect();
+            format!("<BR/>{}{}", type_name(&node.type_name), details)
+        }
+    }
+}
+
+/// `node: id=.. name=.. type=.. kind=..`, see [`DotSettings::metadata_comments`]
+fn node_metadata(node: &DebugNode) -> String {
+    let kind = match node.kind {
+        NodeKind::RenderNode => "render_node",
+        NodeKind::System => "system",
+        NodeKind::ExclusiveSystem => "exclusive_system",
+        NodeKind::SyncPoint => "sync_point",
+        NodeKind::Placeholder => "placeholder",
+        NodeKind::Plugin => "plugin",
+        NodeKind::PluginGroup => "plugin_group",
+    };
+    let mut fields = vec![
+        ("id", node.id.as_str()),
+        ("name", &node.name),
+        ("type", &node.type_name),
+        ("kind", kind),
+    ];
+    if let Some(cluster) = &node.cluster {
+        fields.push(("cluster", cluster));
+    }
+    metadata("node", &fields)
+}
+
+/// `edge: from=.. to=.. kind=..`, with the slot names of slot edges
+fn edge_metadata(graph: &DebugGraph, edge: &DebugEdge) -> String {
+    let kind = match edge.kind {
+        EdgeKind::Slot { .. } => "slot",
+        EdgeKind::Slots(_) => "slots",
+        EdgeKind::Node => "node",
+        EdgeKind::Ordering => "ordering",
+        EdgeKind::Chain => "chain",
+        EdgeKind::Conflict => "conflict",
+        EdgeKind::ReadAfterWrite => "read_after_write",
+        EdgeKind::ClusterOrder => "cluster_order",
+    };
+    let mut fields = vec![("from", edge.from.as_str()), ("to", &edge.to), ("kind", kind)];
+    if let EdgeKind::Slot {
+        output_index,
+        input_index,
+    } = edge.kind
+    {
+        let output = graph.node(&edge.from).and_then(|node| node.outputs.get(output_index));
+        let input = graph.node(&edge.to).and_then(|node| node.inputs.get(input_index));
+        if let (Some(output), Some(input)) = (output, input) {
+            fields.push(("output", &output.name));
+            fields.push(("input", &input.name));
+        }
+    }
+    metadata("edge", &fields)
+}
+
+fn metadata(statement: &str, fields: &[(&str, &str)]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|(key, value)| {
+            let needs_quotes = value.is_empty()
+                || value.contains(|c: char| c.is_whitespace() || c == '"' || c == '=');
+            if needs_quotes {
+                format!("{}={:?}", key, value)
+            } else {
+                format!("{}={}", key, value)
+            }
+        })
+        .collect();
+    format!("{}: {}", statement, fields.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(width: usize, overflow: LabelOverflow) -> DotSettings {
+        DotSettings {
+            max_label_width: Some(width),
+            label_overflow: overflow,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn fit_label_keeps_short_labels() {
+        let settings = settings(8, LabelOverflow::Ellipsis);
+        assert_eq!(fit_label("main_pass", &DotSettings::default()), "main_pass");
+        assert_eq!(fit_label("main", &settings), "main");
+    }
+
+    #[test]
+    fn fit_label_cuts_off_with_ellipsis() {
+        let settings = settings(6, LabelOverflow::Ellipsis);
+        assert_eq!(fit_label("main_pass", &settings), "main_…");
+    }
+
+    #[test]
+    fn fit_label_wraps() {
+        let settings = settings(4, LabelOverflow::Wrap);
+        assert_eq!(fit_label("main_pass", &settings), "main\n_pas\ns");
+    }
+}
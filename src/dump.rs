@@ -0,0 +1,56 @@
+use crate::{
+    render_graph::{render_graph_write, RenderGraphSettings},
+    schedule_graph::{schedule_graph_write, ScheduleGraphSettings},
+};
+use bevy::{app::App, render::render_graph::RenderGraph};
+use std::{
+    fs,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct DumpSettings {
+    pub render_graph: RenderGraphSettings,
+    pub schedule: ScheduleGraphSettings,
+}
+
+/// Writes every graph the app exposes into `dir` (created if missing) and returns the written files:
+/// - `render_graph.dot`, if the app has a [`RenderGraph`]
+/// - `schedule.dot`, including the startup schedule and nested schedules
+///
+/// The render graph of this bevy version has no sub graphs, they are part of `render_graph.dot`.
+pub fn dump_all(
+    app: &App,
+    dir: impl AsRef<Path>,
+    settings: &DumpSettings,
+) -> io::Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+    let mut written = Vec::new();
+
+    if let Some(render_graph) = app.world.get_resource::<RenderGraph>() {
+        let path = dir.join("render_graph.dot");
+        write_file(&path, |writer| {
+            render_graph_write(render_graph, &settings.render_graph, writer)
+        })?;
+        written.push(path);
+    }
+
+    let path = dir.join("schedule.dot");
+    write_file(&path, |writer| {
+        schedule_graph_write(&app.schedule, &settings.schedule, writer)
+    })?;
+    written.push(path);
+
+    Ok(written)
+}
+
+fn write_file(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<fs::File>) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut writer = BufWriter::new(fs::File::create(path)?);
+    write(&mut writer)?;
+    writer.flush()
+}
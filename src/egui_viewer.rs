@@ -0,0 +1,206 @@
+use crate::{
+    graph::{DebugGraph, EdgeKind},
+    utils,
+};
+use bevy::{prelude::*, render::render_graph::RenderGraph, utils::HashMap};
+use bevy_egui::{
+    egui::{self, Align2, Color32, Pos2, Rect, Sense, Stroke, TextStyle, Vec2},
+    EguiContext,
+};
+
+const NODE_SIZE: Vec2 = Vec2 { x: 180.0, y: 44.0 };
+const SPACING: Vec2 = Vec2 { x: 60.0, y: 16.0 };
+
+/// Draws the render graph into an egui window.
+///
+/// The window is toggled with [`RenderGraphViewerSettings::toggle_key`]. The layout is computed
+/// every time the window is opened and recomputed when pressing the refresh button.
+/// Requires the `EguiPlugin` to be added.
+#[derive(Default)]
+pub struct RenderGraphViewerPlugin;
+
+impl Plugin for RenderGraphViewerPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<RenderGraphViewerSettings>()
+            .init_resource::<RenderGraphViewerState>()
+            .add_system(toggle_viewer.system())
+            .add_system(render_graph_viewer.system());
+    }
+}
+
+pub struct RenderGraphViewerSettings {
+    pub toggle_key: KeyCode,
+    /// recompute the layout every frame instead of only when pressing the refresh button
+    pub auto_refresh: bool,
+}
+
+impl Default for RenderGraphViewerSettings {
+    fn default() -> Self {
+        RenderGraphViewerSettings {
+            toggle_key: KeyCode::F9,
+            auto_refresh: false,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RenderGraphViewerState {
+    pub open: bool,
+    layout: Option<GraphLayout>,
+}
+
+struct LayoutNode {
+    title: String,
+    subtitle: String,
+    rect: Rect,
+}
+
+struct GraphLayout {
+    nodes: Vec<LayoutNode>,
+    edges: Vec<(usize, usize)>,
+    size: Vec2,
+}
+
+impl GraphLayout {
+    /// places the nodes in columns by their distance from the graph's inputs
+    fn new(graph: &DebugGraph) -> GraphLayout {
+        let nodes = &graph.nodes;
+        let indices: HashMap<&str, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (node.id.as_str(), index))
+            .collect();
+        let edges: Vec<(usize, usize)> = graph
+            .edges
+            .iter()
+            .filter(|edge| {
+                matches!(
+                    edge.kind,
+                    EdgeKind::Slot { .. } | EdgeKind::Slots(_) | EdgeKind::Node
+                )
+            })
+            .map(|edge| (indices[edge.from.as_str()], indices[edge.to.as_str()]))
+            .collect();
+
+        // bounded by the node count, so cycles don't loop forever
+        let mut depths = vec![0; nodes.len()];
+        for _ in 0..nodes.len() {
+            let mut changed = false;
+            for &(from, to) in &edges {
+                if depths[to] < depths[from] + 1 && depths[from] + 1 < nodes.len() {
+                    depths[to] = depths[from] + 1;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let mut rows = vec![0; nodes.len()];
+        let mut size = Vec2::ZERO;
+        let nodes = nodes
+            .iter()
+            .zip(depths)
+            .map(|(node, depth)| {
+                let row = rows[depth];
+                rows[depth] += 1;
+
+                let min = Pos2::new(
+                    depth as f32 * (NODE_SIZE.x + SPACING.x),
+                    row as f32 * (NODE_SIZE.y + SPACING.y),
+                );
+                let rect = Rect::from_min_size(min, NODE_SIZE);
+                size = size.max(rect.max.to_vec2());
+
+                LayoutNode {
+                    title: node.name.clone(),
+                    subtitle: utils::short_name(&node.type_name),
+                    rect,
+                }
+            })
+            .collect();
+
+        GraphLayout { nodes, edges, size }
+    }
+
+    fn show(&self, ui: &mut egui::Ui) {
+        let (response, painter) = ui.allocate_painter(self.size, Sense::hover());
+        let offset = response.rect.min.to_vec2();
+
+        let edge_stroke = Stroke::new(1.0, Color32::from_rgb(90, 120, 220));
+        for &(from, to) in &self.edges {
+            let start = self.nodes[from].rect.right_center() + offset;
+            let end = self.nodes[to].rect.left_center() + offset;
+            painter.arrow(start, end - start, edge_stroke);
+        }
+
+        let visuals = ui.visuals();
+        let node_stroke = visuals.widgets.noninteractive.fg_stroke;
+        let fill = visuals.widgets.noninteractive.bg_fill;
+        for node in &self.nodes {
+            let rect = node.rect.translate(offset);
+            painter.rect(rect, 4.0, fill, node_stroke);
+            painter.text(
+                rect.center_top() + Vec2::new(0.0, 4.0),
+                Align2::CENTER_TOP,
+                &node.title,
+                TextStyle::Body,
+                node_stroke.color,
+            );
+            painter.text(
+                rect.center_bottom() - Vec2::new(0.0, 4.0),
+                Align2::CENTER_BOTTOM,
+                &node.subtitle,
+                TextStyle::Small,
+                Color32::RED,
+            );
+        }
+    }
+}
+
+fn toggle_viewer(
+    keyboard: Res<Input<KeyCode>>,
+    settings: Res<RenderGraphViewerSettings>,
+    mut state: ResMut<RenderGraphViewerState>,
+) {
+    if keyboard.just_pressed(settings.toggle_key) {
+        state.open = !state.open;
+    }
+}
+
+fn render_graph_viewer(
+    egui_context: Res<EguiContext>,
+    render_graph: Res<RenderGraph>,
+    settings: Res<RenderGraphViewerSettings>,
+    mut state: ResMut<RenderGraphViewerState>,
+) {
+    if !state.open {
+        // laid out again when opened, the graph may have changed in the meantime
+        state.layout = None;
+        return;
+    }
+    if state.layout.is_none() || settings.auto_refresh {
+        state.layout = Some(GraphLayout::new(&DebugGraph::from_render_graph(
+            &render_graph,
+        )));
+    }
+
+    let state = &mut *state;
+    let layout = &mut state.layout;
+    egui::Window::new("Render Graph")
+        .open(&mut state.open)
+        .default_size(Vec2::new(800.0, 500.0))
+        .show(egui_context.ctx(), |ui| {
+            if ui.button("Refresh").clicked() {
+                *layout = Some(GraphLayout::new(&DebugGraph::from_render_graph(
+                    &render_graph,
+                )));
+            }
+            egui::ScrollArea::auto_sized().show(ui, |ui| {
+                if let Some(layout) = layout {
+                    layout.show(ui);
+                }
+            });
+        });
+}
@@ -0,0 +1,177 @@
+use crate::{
+    dot::{quote, DotGraph},
+    dot_exporter::DotSettings,
+    utils,
+};
+use bevy::{
+    ecs::{entity::Entity, reflect::ReflectComponent, world::World},
+    reflect::{Reflect, ReflectRef, TypeRegistryArc},
+};
+
+#[derive(Debug, Clone)]
+pub struct EntityDotSettings {
+    /// only the fonts are used
+    pub dot: DotSettings,
+    /// Number of entries shown of each list and map, the others are summarized in a `… n more` node.
+    pub max_entries: usize,
+}
+
+impl Default for EntityDotSettings {
+    fn default() -> Self {
+        EntityDotSettings {
+            dot: DotSettings::default(),
+            max_entries: 16,
+        }
+    }
+}
+
+/// Renders the entity as a tree of its components and their reflected field values,
+/// a printable alternative to an in-app inspector, e.g. for bug reports.
+///
+/// Components which aren't registered with `#[reflect(Component)]` in the `TypeRegistry` are shown
+/// without fields. Returns `None` if the entity doesn't exist.
+pub fn entity_dot(world: &World, entity: Entity) -> Option<String> {
+    entity_dot_with_settings(world, entity, &EntityDotSettings::default())
+}
+
+pub fn entity_dot_with_settings(
+    world: &World,
+    entity: Entity,
+    settings: &EntityDotSettings,
+) -> Option<String> {
+    let entity_ref = world.get_entity(entity)?;
+    let registry = world.get_resource::<TypeRegistryArc>();
+    let registry = registry.as_ref().map(|registry| registry.read());
+
+    let mut dot = DotGraph::from_writer(Vec::new(), &quote("Entity"), &[("rankdir", "LR")]);
+    let fonts = &settings.dot.fonts;
+    dot.node_attributes(&[
+        ("shape", "box"),
+        ("fontname", &quote(&fonts.family)),
+        ("fontsize", &fonts.detail_size.to_string()),
+    ]);
+    dot.edge_attributes(&[("arrowhead", "none")]);
+
+    let root = "entity";
+    dot.add_node(
+        root,
+        &[
+            ("label", &quote(&format!("{:?}", entity))),
+            ("style", "\"filled,bold\""),
+            ("fillcolor", "\"#e8e8e8\""),
+        ],
+    );
+
+    let mut tree = Tree {
+        dot,
+        next_id: 0,
+        max_entries: settings.max_entries,
+    };
+    let components = world.components();
+    for id in entity_ref.archetype().components() {
+        let info = match components.get_info(id) {
+            Some(info) => info,
+            None => continue,
+        };
+        let reflected = info.type_id().and_then(|type_id| {
+            let registration = registry.as_ref()?.get(type_id)?;
+            registration
+                .data::<ReflectComponent>()?
+                .reflect_component(world, entity)
+        });
+
+        let name = utils::short_name(info.name());
+        let node = match reflected {
+            Some(value) => tree.add_value(root, &name, value),
+            None => tree.add_leaf(root, &format!("{}\n(not reflected)", name)),
+        };
+        tree.dot.add_node(&node, &[("style", "bold")]);
+    }
+
+    Some(tree.dot.finish())
+}
+
+struct Tree {
+    dot: DotGraph,
+    next_id: usize,
+    max_entries: usize,
+}
+
+impl Tree {
+    fn add_leaf(&mut self, parent: &str, label: &str) -> String {
+        let id = format!("n{}", self.next_id);
+        self.next_id += 1;
+        self.dot.add_node(&id, &[("label", &quote(label))]);
+        self.dot.add_edge(parent, None, &id, None, &[]);
+        id
+    }
+
+    /// adds a node for `value`, with a child for each of its fields,
+    /// values without fields are written into the label
+    fn add_value(&mut self, parent: &str, name: &str, value: &dyn Reflect) -> String {
+        let max_entries = self.max_entries;
+        // the entries of lists and maps beyond `max_entries`
+        let mut hidden = 0;
+        let children: Vec<(String, &dyn Reflect)> = match value.reflect_ref() {
+            ReflectRef::Struct(value) => (0..value.field_len())
+                .filter_map(|i| Some((value.name_at(i)?.to_string(), value.field_at(i)?)))
+                .collect(),
+            ReflectRef::TupleStruct(value) => (0..value.field_len())
+                .filter_map(|i| Some((i.to_string(), value.field(i)?)))
+                .collect(),
+            ReflectRef::Tuple(value) => (0..value.field_len())
+                .filter_map(|i| Some((i.to_string(), value.field(i)?)))
+                .collect(),
+            ReflectRef::List(value) => {
+                hidden = value.len().saturating_sub(max_entries);
+                value
+                    .iter()
+                    .take(max_entries)
+                    .enumerate()
+                    .map(|(i, item)| (format!("[{}]", i), item))
+                    .collect()
+            }
+            ReflectRef::Map(value) => {
+                hidden = value.len().saturating_sub(max_entries);
+                value
+                    .iter()
+                    .take(max_entries)
+                    .map(|(key, item)| (format!("[{}]", value_text(key)), item))
+                    .collect()
+            }
+            ReflectRef::Value(value) => {
+                return self.add_leaf(parent, &format!("{}: {}", name, value_text(value)));
+            }
+        };
+
+        let id = self.add_leaf(
+            parent,
+            &format!("{}\n{}", name, utils::short_name(value.type_name())),
+        );
+        for (child_name, child) in children {
+            self.add_value(&id, &child_name, child);
+        }
+        if hidden > 0 {
+            self.add_leaf(&id, &format!("… {} more", hidden));
+        }
+        id
+    }
+}
+
+/// the primitive value, or its type for values which can't be printed
+fn value_text(value: &dyn Reflect) -> String {
+    macro_rules! print_as {
+        ($($ty:ty),*) => {
+            $(
+                if let Some(value) = value.downcast_ref::<$ty>() {
+                    return format!("{:?}", value);
+                }
+            )*
+        };
+    }
+    print_as!(
+        bool, char, String, f32, f64, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128,
+        isize
+    );
+    format!("<{}>", utils::short_name(value.type_name()))
+}
@@ -0,0 +1,158 @@
+use crate::graph::DebugGraph;
+use bevy::utils::HashMap;
+use std::collections::VecDeque;
+
+/// Answers why `to` runs after `from`: returns a copy of the graph in which the shortest chain of directed edges
+/// from `from` to `to` is highlighted and everything else is dimmed. The edges keep their labels,
+/// like the ordering constraints of systems.
+///
+/// Nodes are found by their id, name or type path. Returns `None` if either node doesn't exist
+/// or there is no path between them. Systems in different stages are ordered by their stages,
+/// which isn't a path between the systems.
+pub fn explain_path(graph: &DebugGraph, from: &str, to: &str) -> Option<DebugGraph> {
+    let find = |name: &str| {
+        graph
+            .nodes
+            .iter()
+            .position(|node| node.id == name)
+            .or_else(|| {
+                graph
+                    .nodes
+                    .iter()
+                    .position(|node| node.name == name || node.type_name == name)
+            })
+    };
+    let from = find(from)?;
+    let to = find(to)?;
+
+    let indices: HashMap<&str, usize> = graph
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(index, node)| (node.id.as_str(), index))
+        .collect();
+    let mut successors = vec![Vec::new(); graph.nodes.len()];
+    for edge in graph.edges.iter().filter(|edge| edge.kind.is_directed()) {
+        if let (Some(&from), Some(&to)) = (
+            indices.get(edge.from.as_str()),
+            indices.get(edge.to.as_str()),
+        ) {
+            successors[from].push(to);
+        }
+    }
+
+    // breadth first, so the shortest explanation is found
+    let mut previous: Vec<Option<usize>> = vec![None; graph.nodes.len()];
+    let mut visited = vec![false; graph.nodes.len()];
+    visited[from] = true;
+    let mut queue = VecDeque::new();
+    queue.push_back(from);
+    while let Some(node) = queue.pop_front() {
+        if node == to {
+            break;
+        }
+        for &successor in &successors[node] {
+            if !visited[successor] {
+                visited[successor] = true;
+                previous[successor] = Some(node);
+                queue.push_back(successor);
+            }
+        }
+    }
+    if from != to && previous[to].is_none() {
+        return None;
+    }
+
+    let mut path = vec![to];
+    while let Some(node) = previous[*path.last().unwrap()] {
+        path.push(node);
+    }
+    path.reverse();
+
+    let mut explained = graph.clone();
+    for node in &mut explained.nodes {
+        node.dimmed = !path.iter().any(|&index| graph.nodes[index].id == node.id);
+    }
+    for edge in &mut explained.edges {
+        let on_path = path.windows(2).any(|step| {
+            edge.kind.is_directed()
+                && edge.from == graph.nodes[step[0]].id
+                && edge.to == graph.nodes[step[1]].id
+        });
+        edge.highlighted = on_path;
+        edge.dimmed = !on_path;
+    }
+    Some(explained)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{DebugEdge, DebugNode, EdgeKind, NodeKind};
+
+    fn graph(ids: &[&str], edges: &[(&str, &str)]) -> DebugGraph {
+        let node = |id: &str| DebugNode {
+            id: id.to_string(),
+            name: id.to_string(),
+            type_name: format!("my_game::{}", id),
+            kind: NodeKind::RenderNode,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            cluster: None,
+            details: Vec::new(),
+            access: Vec::new(),
+            dimmed: false,
+        };
+        DebugGraph {
+            name: "Test".to_string(),
+            nodes: ids.iter().map(|id| node(id)).collect(),
+            edges: edges
+                .iter()
+                .map(|&(from, to)| DebugEdge::new(from, to, EdgeKind::Node))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn highlights_shortest_path() {
+        let graph = graph(
+            &["a", "b", "c", "d", "e"],
+            &[("a", "b"), ("b", "c"), ("c", "d"), ("a", "d"), ("d", "e")],
+        );
+
+        let explained = explain_path(&graph, "a", "e").unwrap();
+
+        let highlighted: Vec<_> = explained
+            .edges
+            .iter()
+            .filter(|edge| edge.highlighted)
+            .map(|edge| (edge.from.as_str(), edge.to.as_str()))
+            .collect();
+        assert_eq!(highlighted, vec![("a", "d"), ("d", "e")]);
+        assert!(explained
+            .edges
+            .iter()
+            .all(|edge| edge.highlighted != edge.dimmed));
+        let dimmed: Vec<_> = explained
+            .nodes
+            .iter()
+            .filter(|node| node.dimmed)
+            .map(|node| node.id.as_str())
+            .collect();
+        assert_eq!(dimmed, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn finds_nodes_by_type_name() {
+        let graph = graph(&["a", "b"], &[("a", "b")]);
+        assert!(explain_path(&graph, "my_game::a", "my_game::b").is_some());
+    }
+
+    #[test]
+    fn no_path_against_edge_direction() {
+        let graph = graph(&["a", "b"], &[("a", "b")]);
+        assert!(explain_path(&graph, "b", "a").is_none());
+        assert!(explain_path(&graph, "a", "missing").is_none());
+    }
+}
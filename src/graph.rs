@@ -0,0 +1,805 @@
+use bevy::utils::{HashMap, HashSet};
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+
+/// Backend-agnostic description of a graph extracted from bevy,
+/// e.g. by [`DebugGraph::from_render_graph`] or [`DebugGraph::from_schedule`].
+///
+/// Exporters like the [`DotExporter`](crate::DotExporter) only need to walk this model.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DebugGraph {
+    pub name: String,
+    pub nodes: Vec<DebugNode>,
+    pub edges: Vec<DebugEdge>,
+    /// in the order they are executed, parents before their children
+    pub clusters: Vec<DebugCluster>,
+    /// groups of node ids which should be laid out next to each other
+    pub ranks: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NodeKind {
+    RenderNode,
+    System,
+    ExclusiveSystem,
+    /// point in a stage where the `Commands` of the parallel systems are applied
+    SyncPoint,
+    /// stands in for something which couldn't be extracted, e.g. an unknown stage type
+    Placeholder,
+    Plugin,
+    PluginGroup,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DebugNode {
+    /// unique in the graph, consisting of ascii alphanumerics and `_`
+    pub id: String,
+    pub name: String,
+    /// full type path, or function path for systems
+    pub type_name: String,
+    pub kind: NodeKind,
+    pub inputs: Vec<DebugSlot>,
+    pub outputs: Vec<DebugSlot>,
+    /// id of the innermost cluster containing the node
+    pub cluster: Option<String>,
+    /// additional information only worth showing in verbose output, like the node uuid
+    pub details: Vec<String>,
+    /// components accessed by a system
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub access: Vec<ComponentAccess>,
+    /// only shown as context, e.g. around the path found by [`explain_path`](crate::explain_path)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub dimmed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComponentAccess {
+    pub component: String,
+    pub mutable: bool,
+}
+
+/// Common kinds of render graph nodes, detected from their type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum NodeCategory {
+    /// runs a render pass, like the `PassNode`
+    Pass,
+    /// provides textures or the window swap chain
+    Texture,
+    Camera,
+    /// uploads component or asset data into buffers, like the `RenderResourcesNode`
+    Resources,
+    /// runs another render graph
+    SubGraph,
+    Other,
+}
+
+impl NodeCategory {
+    pub fn name(self) -> &'static str {
+        match self {
+            NodeCategory::Pass => "pass",
+            NodeCategory::Texture => "texture / window",
+            NodeCategory::Camera => "camera",
+            NodeCategory::Resources => "render resources",
+            NodeCategory::SubGraph => "sub graph",
+            NodeCategory::Other => "other",
+        }
+    }
+}
+
+impl DebugNode {
+    /// Guesses the category from the type name, e.g. `bevy_render::pass::PassNode<..>` is a [`NodeCategory::Pass`].
+    pub fn category(&self) -> NodeCategory {
+        let without_generics = self.type_name.split('<').next().unwrap_or(&self.type_name);
+        let name = without_generics
+            .rsplit("::")
+            .next()
+            .unwrap_or(without_generics);
+
+        if name.contains("SubGraph") {
+            NodeCategory::SubGraph
+        } else if name.contains("Pass") {
+            NodeCategory::Pass
+        } else if name.contains("Camera") {
+            NodeCategory::Camera
+        } else if name.contains("Texture") || name.contains("Window") || name.contains("SwapChain")
+        {
+            NodeCategory::Texture
+        } else if name.contains("RenderResources") || name.contains("Buffer") {
+            NodeCategory::Resources
+        } else {
+            NodeCategory::Other
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DebugSlot {
+    pub name: String,
+    pub resource_type: String,
+    /// the resource last bound to the slot, the size and usage of a buffer or the id of a texture or sampler
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub resource: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EdgeKind {
+    /// render graph edge passing a resource from an output slot to an input slot
+    Slot {
+        output_index: usize,
+        input_index: usize,
+    },
+    /// several render graph slot edges between the same pair of nodes,
+    /// as `(output_index, input_index)` pairs
+    Slots(Vec<(usize, usize)>),
+    /// render graph edge only ordering the nodes
+    Node,
+    /// ordering constraint between systems
+    Ordering,
+    /// system piping its output into the next system of a chain
+    Chain,
+    /// unordered systems with conflicting access, undirected
+    Conflict,
+    /// `to` reads a component `from` wrote earlier in the frame
+    ReadAfterWrite,
+    /// execution order between two clusters
+    ClusterOrder,
+}
+
+impl EdgeKind {
+    /// whether the edge orders its nodes, i.e. `from` runs before `to`
+    pub fn is_directed(&self) -> bool {
+        matches!(
+            self,
+            EdgeKind::Slot { .. }
+                | EdgeKind::Slots(_)
+                | EdgeKind::Node
+                | EdgeKind::Ordering
+                | EdgeKind::Chain
+        )
+    }
+
+    /// whether data flows from `from` to `to`, like the output piped through a chain,
+    /// rather than the edge only ordering them
+    pub fn is_data_flow(&self) -> bool {
+        matches!(
+            self,
+            EdgeKind::Slot { .. } | EdgeKind::Slots(_) | EdgeKind::Chain | EdgeKind::ReadAfterWrite
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DebugEdge {
+    /// node id, or cluster id for [`EdgeKind::ClusterOrder`]
+    pub from: String,
+    /// node id, or cluster id for [`EdgeKind::ClusterOrder`]
+    pub to: String,
+    pub kind: EdgeKind,
+    pub label: Option<String>,
+    /// marks edges which are part of a problem, like a cycle
+    pub highlighted: bool,
+    /// only shown as context, e.g. around the path found by [`explain_path`](crate::explain_path)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub dimmed: bool,
+}
+
+impl DebugEdge {
+    pub fn new(from: impl Into<String>, to: impl Into<String>, kind: EdgeKind) -> DebugEdge {
+        DebugEdge {
+            from: from.into(),
+            to: to.into(),
+            kind,
+            label: None,
+            highlighted: false,
+            dimmed: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ClusterKind {
+    Stage,
+    /// a schedule nested as stage of another schedule
+    Schedule,
+    SystemSet,
+    /// systems whose ambiguities are accepted
+    AmbiguitySet,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DebugCluster {
+    pub id: String,
+    pub label: String,
+    pub kind: ClusterKind,
+    pub parent: Option<String>,
+    /// step in seconds of the `FixedTimestep` driving the cluster
+    pub fixed_timestep: Option<f64>,
+}
+
+impl DebugGraph {
+    pub fn node(&self, id: &str) -> Option<&DebugNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    pub fn cluster(&self, id: &str) -> Option<&DebugCluster> {
+        self.clusters.iter().find(|cluster| cluster.id == id)
+    }
+
+    pub fn child_clusters<'a>(
+        &'a self,
+        parent: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a DebugCluster> + 'a {
+        self.clusters
+            .iter()
+            .filter(move |cluster| cluster.parent.as_deref() == parent)
+    }
+
+    pub fn nodes_in<'a>(
+        &'a self,
+        cluster: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a DebugNode> + 'a {
+        self.nodes
+            .iter()
+            .filter(move |node| node.cluster.as_deref() == cluster)
+    }
+
+    /// The nodes in an order they can be executed in, respecting the directed edges.
+    /// Where multiple orders are possible, nodes keep their order in [`DebugGraph::nodes`].
+    /// Nodes which are part of a cycle, or depend on one, can't be ordered and are left out.
+    pub fn topological_order(&self) -> Vec<&DebugNode> {
+        let indices: HashMap<&str, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (node.id.as_str(), index))
+            .collect();
+        let edges: Vec<(usize, usize)> = self
+            .edges
+            .iter()
+            .filter(|edge| edge.kind.is_directed())
+            .filter_map(|edge| {
+                Some((
+                    *indices.get(edge.from.as_str())?,
+                    *indices.get(edge.to.as_str())?,
+                ))
+            })
+            .collect();
+
+        let mut dependencies = vec![0; self.nodes.len()];
+        for &(_, to) in &edges {
+            dependencies[to] += 1;
+        }
+        let mut ready: BTreeSet<usize> = (0..self.nodes.len())
+            .filter(|&index| dependencies[index] == 0)
+            .collect();
+
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(index) = ready.iter().next().copied() {
+            ready.remove(&index);
+            order.push(&self.nodes[index]);
+            for &(_, to) in edges.iter().filter(|&&(from, _)| from == index) {
+                dependencies[to] -= 1;
+                if dependencies[to] == 0 {
+                    ready.insert(to);
+                }
+            }
+        }
+        order
+    }
+
+    /// Puts the nodes with the same depth, i.e. the length of the longest chain of directed edges
+    /// leading to them, into the same rank, so nodes which can run at the same point line up.
+    /// Nodes which are part of a cycle have no depth and are left to the layout.
+    pub fn rank_by_execution_layers(&mut self) {
+        let mut depths: HashMap<&str, usize> = HashMap::default();
+        for node in self.topological_order() {
+            let depth = self
+                .edges
+                .iter()
+                .filter(|edge| edge.kind.is_directed() && edge.to == node.id)
+                .filter_map(|edge| depths.get(edge.from.as_str()))
+                .map(|depth| depth + 1)
+                .max()
+                .unwrap_or(0);
+            depths.insert(&node.id, depth);
+        }
+
+        // ranks can't span clusters
+        let mut keys: Vec<(usize, Option<&str>)> = Vec::new();
+        let mut layers: Vec<Vec<String>> = Vec::new();
+        for node in &self.nodes {
+            let key = match depths.get(node.id.as_str()) {
+                Some(&depth) => (depth, node.cluster.as_deref()),
+                None => continue,
+            };
+            match keys.iter().position(|layer| *layer == key) {
+                Some(index) => layers[index].push(node.id.clone()),
+                None => {
+                    keys.push(key);
+                    layers.push(vec![node.id.clone()]);
+                }
+            }
+        }
+        let mut layers: Vec<_> = keys.into_iter().zip(layers).collect();
+        layers.sort_by_key(|&((depth, _), _)| depth);
+
+        let ranks: Vec<Vec<String>> = layers
+            .into_iter()
+            .map(|(_, ids)| ids)
+            .filter(|ids| ids.len() > 1)
+            .collect();
+        self.ranks.extend(ranks);
+    }
+
+    /// Prefixes the node names with their position in the [`DebugGraph::topological_order`], e.g. `#07 main_pass`.
+    pub fn number_in_execution_order(&mut self) {
+        let order: Vec<String> = self
+            .topological_order()
+            .into_iter()
+            .map(|node| node.id.clone())
+            .collect();
+        let width = order.len().to_string().len().max(2);
+        for (position, id) in order.iter().enumerate() {
+            if let Some(node) = self.nodes.iter_mut().find(|node| &node.id == id) {
+                node.name = format!("#{:0width$} {}", position + 1, node.name, width = width);
+            }
+        }
+    }
+
+    /// Replaces the names, type paths, slot names and accessed components of the render nodes, systems and plugins
+    /// not entirely defined by bevy with stable hashes, so the graph can be shared publicly without revealing
+    /// the project's internals. The same name is always replaced with the same hash.
+    ///
+    /// Instances of generic bevy types are redacted as well if a generic argument is a user type,
+    /// like `RenderResourcesNode<my_game::MyMaterial>`. Only the user type paths in their type name are replaced.
+    pub fn redact(&mut self) {
+        let redacted: HashMap<String, String> = self
+            .nodes
+            .iter()
+            .filter(|node| is_user_defined(node))
+            .map(|node| (node.id.clone(), format!("n_{}", stable_hash(&node.id))))
+            .collect();
+        let redact_id = |id: &mut String| {
+            if let Some(new_id) = redacted.get(id) {
+                *id = new_id.clone();
+            }
+        };
+
+        for node in &mut self.nodes {
+            if !redacted.contains_key(&node.id) {
+                continue;
+            }
+            node.name = format!("node_{}", stable_hash(&node.name));
+            node.type_name = redact_type_name(&node.type_name);
+            for slot in node.inputs.iter_mut().chain(&mut node.outputs) {
+                slot.name = format!("slot_{}", stable_hash(&slot.name));
+            }
+            for access in &mut node.access {
+                access.component = format!("component_{}", stable_hash(&access.component));
+            }
+            redact_id(&mut node.id);
+        }
+
+        for edge in &mut self.edges {
+            // labels name the slots or system labels of the nodes
+            if redacted.contains_key(&edge.from) || redacted.contains_key(&edge.to) {
+                edge.label = None;
+            }
+            redact_id(&mut edge.from);
+            redact_id(&mut edge.to);
+        }
+        for id in self.ranks.iter_mut().flatten() {
+            redact_id(id);
+        }
+    }
+
+    /// Removes the nodes not matching `keep`, together with their edges.
+    /// Nodes which were connected through removed nodes stay connected by a direct edge,
+    /// so the ordering between the remaining nodes can still be read from the graph.
+    pub fn retain_nodes(&mut self, keep: impl Fn(&DebugNode) -> bool) {
+        let removed: HashSet<String> = self
+            .nodes
+            .iter()
+            .filter(|node| !keep(node))
+            .map(|node| node.id.clone())
+            .collect();
+        if removed.is_empty() {
+            return;
+        }
+
+        let mut collapsed: Vec<(String, String)> = Vec::new();
+        for edge in self.edges.iter().filter(|edge| edge.kind.is_directed()) {
+            if removed.contains(&edge.from) || !removed.contains(&edge.to) {
+                continue;
+            }
+
+            // walk through the removed nodes until reaching the kept ones
+            let mut visited = HashSet::default();
+            let mut stack = vec![edge.to.as_str()];
+            while let Some(id) = stack.pop() {
+                if !visited.insert(id) {
+                    continue;
+                }
+                let successors = self
+                    .edges
+                    .iter()
+                    .filter(|next| next.kind.is_directed() && next.from == id)
+                    .map(|next| next.to.as_str());
+                for successor in successors {
+                    if removed.contains(successor) {
+                        stack.push(successor);
+                    } else if successor != edge.from {
+                        let pair = (edge.from.clone(), successor.to_string());
+                        if !collapsed.contains(&pair) {
+                            collapsed.push(pair);
+                        }
+                    }
+                }
+            }
+        }
+
+        let is_render_node =
+            |id: &str| matches!(self.node(id), Some(node) if node.kind == NodeKind::RenderNode);
+        let collapsed: Vec<DebugEdge> = collapsed
+            .into_iter()
+            .filter(|(from, to)| {
+                !self
+                    .edges
+                    .iter()
+                    .any(|edge| edge.kind.is_directed() && &edge.from == from && &edge.to == to)
+            })
+            .map(|(from, to)| {
+                let kind = if is_render_node(&from) && is_render_node(&to) {
+                    EdgeKind::Node
+                } else {
+                    EdgeKind::Ordering
+                };
+                DebugEdge::new(from, to, kind)
+            })
+            .collect();
+
+        self.nodes.retain(|node| !removed.contains(&node.id));
+        self.edges.retain(|edge| {
+            edge.kind == EdgeKind::ClusterOrder
+                || !(removed.contains(&edge.from) || removed.contains(&edge.to))
+        });
+        self.edges.extend(collapsed);
+        for rank in &mut self.ranks {
+            rank.retain(|id| !removed.contains(id));
+        }
+        self.ranks.retain(|rank| !rank.is_empty());
+    }
+
+    /// Replaces multiple slot edges between the same pair of nodes with a single
+    /// [`EdgeKind::Slots`] edge, labeled with the connected slots.
+    pub fn merge_slot_edges(&mut self) {
+        let mut merged: Vec<DebugEdge> = Vec::new();
+        for edge in self.edges.drain(..) {
+            let (output_index, input_index) = match edge.kind {
+                EdgeKind::Slot {
+                    output_index,
+                    input_index,
+                } => (output_index, input_index),
+                _ => {
+                    merged.push(edge);
+                    continue;
+                }
+            };
+
+            let existing = merged.iter_mut().find(|other| {
+                matches!(other.kind, EdgeKind::Slot { .. } | EdgeKind::Slots(_))
+                    && other.from == edge.from
+                    && other.to == edge.to
+            });
+            match existing {
+                Some(existing) => {
+                    if let EdgeKind::Slot {
+                        output_index,
+                        input_index,
+                    } = existing.kind
+                    {
+                        existing.kind = EdgeKind::Slots(vec![(output_index, input_index)]);
+                    }
+                    if let EdgeKind::Slots(slots) = &mut existing.kind {
+                        slots.push((output_index, input_index));
+                    }
+                    existing.highlighted |= edge.highlighted;
+                }
+                None => merged.push(edge),
+            }
+        }
+
+        for edge in &mut merged {
+            if let EdgeKind::Slots(slots) = &edge.kind {
+                let slot_name = |id: &str, index: usize, outputs: bool| {
+                    self.node(id)
+                        .and_then(|node| {
+                            if outputs {
+                                node.outputs.get(index)
+                            } else {
+                                node.inputs.get(index)
+                            }
+                        })
+                        .map_or_else(|| format!("#{}", index), |slot| slot.name.clone())
+                };
+                let lines: Vec<String> = slots
+                    .iter()
+                    .map(|&(output, input)| {
+                        format!(
+                            "{} -> {}",
+                            slot_name(&edge.from, output, true),
+                            slot_name(&edge.to, input, false)
+                        )
+                    })
+                    .collect();
+                edge.label = Some(lines.join("\n"));
+            }
+        }
+
+        self.edges = merged;
+    }
+}
+
+/// FNV-1a, which unlike the std hasher is guaranteed to stay the same between runs and rust versions
+fn stable_hash(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:08x}", hash as u32)
+}
+
+/// nodes and systems defined by bevy itself, judged by their type path
+pub(crate) fn is_bevy_internal(node: &DebugNode) -> bool {
+    node.type_name.starts_with("bevy_") || node.type_name.starts_with("bevy::")
+}
+
+/// render nodes, systems and plugins with a type path outside of bevy and std, including the generic arguments,
+/// as opposed to e.g. sync points, which have no type
+fn is_user_defined(node: &DebugNode) -> bool {
+    let named_by_user = matches!(
+        node.kind,
+        NodeKind::RenderNode
+            | NodeKind::System
+            | NodeKind::ExclusiveSystem
+            | NodeKind::Plugin
+            | NodeKind::PluginGroup
+    );
+    named_by_user && split_type_paths(&node.type_name).any(|(path, _)| is_user_path(path))
+}
+
+/// replaces the user type paths in the type name with hashes, keeping bevy's like `PassNode<type_1a2b3c4d>`
+fn redact_type_name(type_name: &str) -> String {
+    split_type_paths(type_name)
+        .map(|(path, separator)| {
+            let path = if is_user_path(path) {
+                format!("type_{}", stable_hash(path))
+            } else {
+                path.to_string()
+            };
+            format!("{}{}", path, separator)
+        })
+        .collect()
+}
+
+/// the words of the type name, like `my_game::MyMaterial` or `u32`, with the text following them, like `>, `
+fn split_type_paths(type_name: &str) -> impl Iterator<Item = (&str, &str)> {
+    let is_path_char = |c: char| c.is_alphanumeric() || c == '_' || c == ':';
+    let mut rest = type_name;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let path_end = rest
+            .find(|c: char| !is_path_char(c))
+            .unwrap_or_else(|| rest.len());
+        let separator_end = rest[path_end..]
+            .find(is_path_char)
+            .map_or(rest.len(), |index| path_end + index);
+        let split = (&rest[..path_end], &rest[path_end..separator_end]);
+        rest = &rest[separator_end..];
+        Some(split)
+    })
+}
+
+fn is_user_path(path: &str) -> bool {
+    let foreign = ["bevy_", "bevy::", "std::", "core::", "alloc::"];
+    path.contains("::") && !foreign.iter().any(|prefix| path.starts_with(prefix))
+}
+
+/// Turns a [`DebugGraph`] into some output format.
+pub trait GraphExporter {
+    fn export(&self, graph: &DebugGraph, writer: &mut dyn Write) -> io::Result<()>;
+
+    fn export_to_string(&self, graph: &DebugGraph) -> String {
+        let mut buffer = Vec::new();
+        self.export(graph, &mut buffer)
+            .expect("writing into a Vec<u8> cannot fail");
+        String::from_utf8(buffer).expect("exporter produced invalid utf8")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str) -> DebugNode {
+        DebugNode {
+            id: id.to_string(),
+            name: id.to_string(),
+            type_name: format!("my_game::{}", id),
+            kind: NodeKind::RenderNode,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            cluster: None,
+            details: Vec::new(),
+            access: Vec::new(),
+            dimmed: false,
+        }
+    }
+
+    fn graph(ids: &[&str], edges: &[(&str, &str)]) -> DebugGraph {
+        DebugGraph {
+            name: "Test".to_string(),
+            nodes: ids.iter().map(|id| node(id)).collect(),
+            edges: edges
+                .iter()
+                .map(|&(from, to)| DebugEdge::new(from, to, EdgeKind::Node))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn slot(name: &str) -> DebugSlot {
+        DebugSlot {
+            name: name.to_string(),
+            resource_type: "Texture".to_string(),
+            resource: None,
+        }
+    }
+
+    fn ids<'a>(nodes: impl IntoIterator<Item = &'a DebugNode>) -> Vec<&'a str> {
+        nodes.into_iter().map(|node| node.id.as_str()).collect()
+    }
+
+    #[test]
+    fn retain_nodes_connects_through_removed_nodes() {
+        let mut graph = graph(
+            &["a", "hidden_1", "hidden_2", "b"],
+            &[
+                ("a", "hidden_1"),
+                ("hidden_1", "hidden_2"),
+                ("hidden_2", "b"),
+            ],
+        );
+
+        graph.retain_nodes(|node| !node.id.starts_with("hidden"));
+
+        assert_eq!(ids(&graph.nodes), vec!["a", "b"]);
+        assert_eq!(graph.edges.len(), 1);
+        assert_eq!(
+            (graph.edges[0].from.as_str(), graph.edges[0].to.as_str()),
+            ("a", "b")
+        );
+        assert_eq!(graph.edges[0].kind, EdgeKind::Node);
+    }
+
+    #[test]
+    fn retain_nodes_doesnt_duplicate_existing_edges() {
+        let mut graph = graph(
+            &["a", "hidden", "b"],
+            &[("a", "hidden"), ("hidden", "b"), ("a", "b")],
+        );
+
+        graph.retain_nodes(|node| node.id != "hidden");
+
+        assert_eq!(graph.edges.len(), 1);
+    }
+
+    #[test]
+    fn merge_slot_edges_labels_merged_slots() {
+        let mut graph = graph(&["a", "b"], &[]);
+        graph.nodes[0].outputs = vec![slot("color"), slot("depth")];
+        graph.nodes[1].inputs = vec![slot("color_in"), slot("depth_in")];
+        for index in 0..2 {
+            let kind = EdgeKind::Slot {
+                output_index: index,
+                input_index: index,
+            };
+            graph.edges.push(DebugEdge::new("a", "b", kind));
+        }
+        graph.edges.push(DebugEdge::new("a", "b", EdgeKind::Node));
+
+        graph.merge_slot_edges();
+
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!(graph.edges[0].kind, EdgeKind::Slots(vec![(0, 0), (1, 1)]));
+        assert_eq!(
+            graph.edges[0].label.as_deref(),
+            Some("color -> color_in\ndepth -> depth_in")
+        );
+        assert_eq!(graph.edges[1].kind, EdgeKind::Node);
+    }
+
+    #[test]
+    fn merge_slot_edges_keeps_single_slot_edges() {
+        let mut graph = graph(&["a", "b"], &[]);
+        let kind = EdgeKind::Slot {
+            output_index: 0,
+            input_index: 0,
+        };
+        graph.edges.push(DebugEdge::new("a", "b", kind.clone()));
+
+        graph.merge_slot_edges();
+
+        assert_eq!(graph.edges.len(), 1);
+        assert_eq!(graph.edges[0].kind, kind);
+        assert_eq!(graph.edges[0].label, None);
+    }
+
+    #[test]
+    fn topological_order_follows_edges() {
+        let graph = graph(&["c", "b", "a"], &[("a", "b"), ("b", "c")]);
+        assert_eq!(ids(graph.topological_order()), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn topological_order_keeps_node_order_of_unordered_nodes() {
+        let graph = graph(&["b", "a", "c"], &[("a", "c")]);
+        assert_eq!(ids(graph.topological_order()), vec!["b", "a", "c"]);
+    }
+
+    #[test]
+    fn topological_order_leaves_out_cycles() {
+        let graph = graph(&["a", "b", "c", "d"], &[("b", "c"), ("c", "b"), ("c", "d")]);
+        assert_eq!(ids(graph.topological_order()), vec!["a"]);
+    }
+
+    #[test]
+    fn number_in_execution_order_prefixes_names() {
+        let mut graph = graph(&["b", "a"], &[("a", "b")]);
+        graph.number_in_execution_order();
+        let names: Vec<&str> = graph.nodes.iter().map(|node| node.name.as_str()).collect();
+        assert_eq!(names, vec!["#02 b", "#01 a"]);
+    }
+
+    #[test]
+    fn redact_hashes_user_types_and_generic_arguments() {
+        let mut graph = graph(&["my_pass", "material", "main_pass", "apply_commands"], &[]);
+        graph.nodes[1].type_name =
+            "bevy_render::render_graph::nodes::render_resources_node::RenderResourcesNode<my_game::MyMaterial>"
+                .to_string();
+        graph.nodes[2].type_name =
+            "bevy_render::render_graph::nodes::pass_node::PassNode<&bevy_pbr::render_graph::MainPass>"
+                .to_string();
+        graph.nodes[3].type_name = String::new();
+        graph.nodes[3].kind = NodeKind::SyncPoint;
+
+        graph.redact();
+
+        let names: Vec<&str> = graph.nodes.iter().map(|node| node.name.as_str()).collect();
+        assert!(names[0].starts_with("node_"));
+        assert!(names[1].starts_with("node_"));
+        assert_eq!(names[2..], ["main_pass", "apply_commands"]);
+        assert!(graph.nodes[0].type_name.starts_with("type_"));
+        assert_eq!(
+            graph.nodes[1].type_name,
+            format!(
+                "bevy_render::render_graph::nodes::render_resources_node::RenderResourcesNode<type_{}>",
+                stable_hash("my_game::MyMaterial")
+            )
+        );
+        assert!(graph
+            .nodes
+            .iter()
+            .all(|node| !node.type_name.contains("my_game")));
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{render_graph_dot, schedule_graph_dot};
+use bevy::{prelude::*, render::render_graph::RenderGraph};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Mutex,
+    },
+    time::Duration,
+};
+
+/// Serves the dot output on `GET /render_graph.dot` and `GET /schedule.dot`.
+///
+/// The render graph is regenerated for every request. The schedule isn't accessible while the app is running,
+/// so `/schedule.dot` serves a snapshot taken by calling [`DebugServerPlugin::capture_schedule`] after all
+/// systems are added, and answers 404 until then. Changes to the schedule after the capture aren't served.
+/// Requests the app doesn't answer within 5 seconds, e.g. while it is paused or loading, get a 503.
+pub struct DebugServerPlugin {
+    pub address: SocketAddr,
+}
+
+impl Default for DebugServerPlugin {
+    fn default() -> Self {
+        DebugServerPlugin {
+            address: SocketAddr::from(([127, 0, 0, 1], 8787)),
+        }
+    }
+}
+
+impl DebugServerPlugin {
+    pub fn capture_schedule(app: &mut AppBuilder) {
+        let dot = schedule_graph_dot(&app.app.schedule);
+        app.insert_resource(CapturedSchedule(dot));
+    }
+}
+
+impl Plugin for DebugServerPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        let listener = match TcpListener::bind(self.address) {
+            Ok(listener) => listener,
+            Err(error) => {
+                bevy::log::error!(
+                    "failed to start debug server on {}: {}",
+                    self.address,
+                    error
+                );
+                return;
+            }
+        };
+        bevy::log::info!("serving debug graphs on http://{}", self.address);
+
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                // a slow client or a busy frame mustn't hold up the other connections
+                let sender = sender.clone();
+                std::thread::spawn(move || {
+                    if let Err(error) = handle_connection(stream, &sender) {
+                        bevy::log::warn!("debug server: {}", error);
+                    }
+                });
+            }
+        });
+
+        app.insert_resource(DebugServerRequests(Mutex::new(receiver)))
+            .add_system(answer_requests.system());
+    }
+}
+
+struct CapturedSchedule(String);
+
+#[derive(Clone, Copy)]
+enum Endpoint {
+    RenderGraph,
+    Schedule,
+}
+
+struct DebugServerRequests(Mutex<Receiver<(Endpoint, Sender<Option<String>>)>>);
+
+fn answer_requests(
+    requests: Res<DebugServerRequests>,
+    render_graph: Res<RenderGraph>,
+    schedule: Option<Res<CapturedSchedule>>,
+) {
+    let requests = requests.0.lock().unwrap();
+    for (endpoint, response) in requests.try_iter() {
+        let dot = match endpoint {
+            Endpoint::RenderGraph => Some(render_graph_dot(&render_graph)),
+            Endpoint::Schedule => schedule.as_ref().map(|schedule| schedule.0.clone()),
+        };
+        let _ = response.send(dot);
+    }
+}
+
+fn handle_connection(
+    mut stream: TcpStream,
+    requests: &Sender<(Endpoint, Sender<Option<String>>)>,
+) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // read up to the blank line ending the headers, as closing with unread data resets the connection
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim_end().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next();
+    // ignore query strings, viewers like to append them to avoid caching
+    let path = parts.next().and_then(|path| path.split('?').next());
+    let endpoint = match (method, path) {
+        (Some("GET"), Some("/render_graph.dot")) => Some(Endpoint::RenderGraph),
+        (Some("GET"), Some("/schedule.dot")) => Some(Endpoint::Schedule),
+        _ => None,
+    };
+
+    let response = match endpoint {
+        Some(endpoint) => {
+            let (sender, receiver) = mpsc::channel();
+            let answer = requests
+                .send((endpoint, sender))
+                .ok()
+                .and_then(|()| receiver.recv_timeout(Duration::from_secs(5)).ok());
+            match answer {
+                Some(Some(dot)) => Ok(dot),
+                // e.g. the schedule before it is captured
+                Some(None) => Err("404 Not Found"),
+                // the app didn't answer, e.g. because it is stalled, paused or still loading
+                None => Err("503 Service Unavailable"),
+            }
+        }
+        None => Err("404 Not Found"),
+    };
+
+    match response {
+        Ok(dot) => write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/vnd.graphviz\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            dot.len(),
+            dot
+        ),
+        Err(status) => write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            status
+        ),
+    }
+}
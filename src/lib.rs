@@ -1,5 +1,100 @@
 mod dot;
+pub use dot::html_escape;
 mod utils;
 
+mod graph;
+pub use graph::{
+    ClusterKind, ComponentAccess, DebugCluster, DebugEdge, DebugGraph, DebugNode, DebugSlot,
+    EdgeKind, GraphExporter, NodeCategory, NodeKind,
+};
+
+mod dot_exporter;
+pub use dot_exporter::{
+    DotExporter, DotSettings, FontSettings, LabelFormatter, LabelOverflow, LayoutEngine,
+    LayoutSettings, Palette, SlotOrder, Verbosity,
+};
+
+mod mermaid_exporter;
+pub use mermaid_exporter::MermaidExporter;
+
+mod chrome_trace;
+pub use chrome_trace::ChromeTraceExporter;
+
 mod render_graph;
-pub use render_graph::render_graph_dot;
+pub use render_graph::{
+    iter_dot_stmts, iter_dot_stmts_with_settings, print_render_graph, render_graph_dot,
+    render_graph_dot_from_app, render_graph_dot_from_world, render_graph_dot_with_bound_resources,
+    render_graph_dot_with_settings, render_graph_write, RenderGraphIds, RenderGraphSettings,
+};
+
+mod view_graph;
+pub use view_graph::render_graph_dot_for_camera;
+
+mod cycles;
+pub use cycles::{find_cycles, RenderGraphCycle};
+
+mod mismatches;
+pub use mismatches::{find_slot_mismatches, SlotMismatch};
+
+mod validate;
+pub use validate::{validate, GraphIssue, GraphReport};
+
+mod explain;
+pub use explain::explain_path;
+
+mod schedule_graph;
+pub use schedule_graph::{
+    print_schedule, schedule_graph_dot, schedule_graph_dot_for, schedule_graph_dot_with_settings,
+    schedule_graph_write, stage_graph_dot_with_settings, ScheduleGraphSettings,
+};
+
+mod plugin_graph;
+pub use plugin_graph::{
+    plugin_graph_dot, plugin_graph_dot_with_settings, RecordPlugins, RecordedPlugin,
+    RecordedPlugins,
+};
+
+mod world_summary;
+pub use world_summary::{world_summary, ComponentSummary, WorldSummary};
+
+mod entity_dot;
+pub use entity_dot::{entity_dot, entity_dot_with_settings, EntityDotSettings};
+
+#[cfg(feature = "egui")]
+mod egui_viewer;
+#[cfg(feature = "egui")]
+pub use egui_viewer::{RenderGraphViewerPlugin, RenderGraphViewerSettings, RenderGraphViewerState};
+
+mod dump;
+pub use dump::{dump_all, DumpSettings};
+
+mod snapshot_testing;
+pub use snapshot_testing::{assert_dot_snapshot, normalize_dot, UPDATE_SNAPSHOTS_VAR};
+
+mod watch;
+pub use watch::RenderGraphWatchPlugin;
+
+mod capture;
+pub use capture::RenderGraphCapturePlugin;
+
+#[cfg(feature = "debug_server")]
+mod http_server;
+#[cfg(feature = "debug_server")]
+pub use http_server::DebugServerPlugin;
+
+#[cfg(feature = "clipboard")]
+mod clipboard;
+#[cfg(feature = "clipboard")]
+pub use clipboard::{copy_to_clipboard, ClipboardError, CopyToClipboardPlugin};
+
+#[cfg(feature = "snapshot")]
+mod snapshot;
+#[cfg(feature = "snapshot")]
+pub use snapshot::{
+    load_snapshot, save_graph_snapshot, save_snapshot, Snapshot, SnapshotError, SNAPSHOT_VERSION,
+};
+
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "wasm")]
+pub use wasm::{download, log_to_console};
@@ -0,0 +1,111 @@
+use crate::{
+    graph::{DebugEdge, DebugGraph, DebugNode, EdgeKind, GraphExporter, NodeKind},
+    utils,
+};
+use std::io::{self, Write};
+
+/// Writes the graph as a mermaid flowchart, which renders directly in github and gitlab markdown.
+///
+/// Mermaid has no ports or ranks, so slots are written onto the edges and
+/// the layout is left to mermaid.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MermaidExporter;
+
+impl GraphExporter for MermaidExporter {
+    fn export(&self, graph: &DebugGraph, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(writer, "flowchart LR")?;
+        write_contents(writer, graph, None, 1)?;
+
+        let mut highlighted = Vec::new();
+        for (index, edge) in graph.edges.iter().enumerate() {
+            let label = match &edge.kind {
+                EdgeKind::Slot {
+                    output_index,
+                    input_index,
+                } => slot_label(graph, edge, *output_index, *input_index),
+                _ => edge.label.clone(),
+            };
+            let arrow = match edge.kind {
+                EdgeKind::Chain => "==>",
+                EdgeKind::Conflict => "-.-",
+                EdgeKind::ReadAfterWrite => "-.->",
+                _ => "-->",
+            };
+            let from = id(&edge.from);
+            let to = id(&edge.to);
+            match label {
+                Some(label) => writeln!(writer, "    {} {}|{}| {}", from, arrow, text(&label), to)?,
+                None => writeln!(writer, "    {} {} {}", from, arrow, to)?,
+            }
+            if edge.highlighted {
+                highlighted.push(index.to_string());
+            }
+        }
+        if !highlighted.is_empty() {
+            writeln!(
+                writer,
+                "    linkStyle {} stroke:red,stroke-width:2px",
+                highlighted.join(",")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn write_contents(
+    writer: &mut dyn Write,
+    graph: &DebugGraph,
+    cluster: Option<&str>,
+    depth: usize,
+) -> io::Result<()> {
+    let indent = "    ".repeat(depth);
+    for child in graph.child_clusters(cluster) {
+        writeln!(
+            writer,
+            "{}subgraph {} [{}]",
+            indent,
+            id(&child.id),
+            text(&child.label)
+        )?;
+        write_contents(writer, graph, Some(&child.id), depth + 1)?;
+        writeln!(writer, "{}end", indent)?;
+    }
+    for node in graph.nodes_in(cluster) {
+        writeln!(writer, "{}{}", indent, node_statement(node))?;
+    }
+    Ok(())
+}
+
+fn node_statement(node: &DebugNode) -> String {
+    let label = text(&node.name);
+    let id = id(&node.id);
+    match node.kind {
+        NodeKind::ExclusiveSystem | NodeKind::PluginGroup => format!("{}[[{}]]", id, label),
+        NodeKind::SyncPoint => format!("{}{{{{{}}}}}", id, label),
+        NodeKind::Placeholder => format!("{}([{}])", id, label),
+        NodeKind::RenderNode | NodeKind::System | NodeKind::Plugin => {
+            format!("{}[{}]", id, label)
+        }
+    }
+}
+
+fn slot_label(
+    graph: &DebugGraph,
+    edge: &DebugEdge,
+    output_index: usize,
+    input_index: usize,
+) -> Option<String> {
+    let output = graph.node(&edge.from)?.outputs.get(output_index)?;
+    let input = graph.node(&edge.to)?.inputs.get(input_index)?;
+    Some(format!("{} -> {}", output.name, input.name))
+}
+
+/// mermaid ids may only contain a restricted set of characters, unlike the ids of the model
+fn id(id: &str) -> String {
+    utils::sanitize_id(id)
+}
+
+/// quoted text, which may contain any character except for quotes
+fn text(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "#quot;").replace('\n', "<br/>"))
+}
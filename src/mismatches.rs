@@ -0,0 +1,56 @@
+use crate::render_graph::sorted_nodes;
+use bevy::render::{
+    render_graph::{Edge, NodeId, RenderGraph},
+    renderer::RenderResourceType,
+};
+
+/// A slot edge connecting slots of different resource types.
+#[derive(Debug, Clone)]
+pub struct SlotMismatch {
+    pub edge: Edge,
+    pub output_node: NodeId,
+    pub output_type: RenderResourceType,
+    pub input_node: NodeId,
+    pub input_type: RenderResourceType,
+}
+
+/// Finds the slot edges whose output slot type differs from the input slot type,
+/// a common mistake when wiring up custom nodes.
+pub fn find_slot_mismatches(graph: &RenderGraph) -> Vec<SlotMismatch> {
+    let nodes = sorted_nodes(graph);
+    let node = |id: NodeId| nodes.iter().find(|node| node.id == id);
+
+    nodes
+        .iter()
+        .flat_map(|node| &node.edges.input_edges)
+        .filter_map(|edge| match *edge {
+            Edge::SlotEdge {
+                input_node,
+                input_index,
+                output_node,
+                output_index,
+            } => {
+                let output_type = node(output_node)?
+                    .output_slots
+                    .iter()
+                    .nth(output_index)?
+                    .info
+                    .resource_type;
+                let input_type = node(input_node)?
+                    .input_slots
+                    .iter()
+                    .nth(input_index)?
+                    .info
+                    .resource_type;
+                (output_type != input_type).then(|| SlotMismatch {
+                    edge: edge.clone(),
+                    output_node,
+                    output_type,
+                    input_node,
+                    input_type,
+                })
+            }
+            Edge::NodeEdge { .. } => None,
+        })
+        .collect()
+}
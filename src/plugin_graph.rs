@@ -0,0 +1,144 @@
+use crate::{
+    dot_exporter::{DotExporter, DotSettings},
+    graph::{DebugEdge, DebugGraph, DebugNode, EdgeKind, GraphExporter, NodeKind},
+    utils,
+};
+use bevy::prelude::*;
+
+/// Plugins added through [`RecordPlugins`], in the order they were registered.
+#[derive(Debug, Clone, Default)]
+pub struct RecordedPlugins {
+    pub plugins: Vec<RecordedPlugin>,
+    /// plugins whose `build` is currently running
+    building: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordedPlugin {
+    pub name: String,
+    /// index of the recorded plugin which added this one from its `build`
+    pub parent: Option<usize>,
+    pub group: bool,
+}
+
+/// The app doesn't keep track of its plugins, so they need to be added through these methods to be recorded.
+///
+/// Plugins added by a recorded plugin are only known if it uses these methods itself.
+/// The members of a plugin group aren't exposed, so a group is recorded as a single node.
+pub trait RecordPlugins {
+    fn add_recorded_plugin<T: Plugin>(&mut self, plugin: T) -> &mut Self;
+    fn add_recorded_plugins<T: PluginGroup>(&mut self, group: T) -> &mut Self;
+}
+
+impl RecordPlugins for AppBuilder {
+    fn add_recorded_plugin<T: Plugin>(&mut self, plugin: T) -> &mut Self {
+        let name = plugin.name().to_string();
+        record(self, name, false, |app| {
+            app.add_plugin(plugin);
+        })
+    }
+
+    fn add_recorded_plugins<T: PluginGroup>(&mut self, group: T) -> &mut Self {
+        let name = std::any::type_name::<T>().to_string();
+        record(self, name, true, |app| {
+            app.add_plugins(group);
+        })
+    }
+}
+
+fn record(
+    app: &mut AppBuilder,
+    name: String,
+    group: bool,
+    add: impl FnOnce(&mut AppBuilder),
+) -> &mut AppBuilder {
+    // the resource is taken out while the plugin builds, so plugins are free to access the world
+    let mut recorded = take_recorded(app);
+    let index = recorded.plugins.len();
+    recorded.plugins.push(RecordedPlugin {
+        name,
+        parent: recorded.building.last().copied(),
+        group,
+    });
+    recorded.building.push(index);
+    app.app.world.insert_resource(recorded);
+
+    add(app);
+
+    let mut recorded = take_recorded(app);
+    recorded.building.pop();
+    app.app.world.insert_resource(recorded);
+    app
+}
+
+fn take_recorded(app: &mut AppBuilder) -> RecordedPlugins {
+    app.app
+        .world
+        .remove_resource::<RecordedPlugins>()
+        .unwrap_or_default()
+}
+
+pub fn plugin_graph_dot(plugins: &RecordedPlugins) -> String {
+    plugin_graph_dot_with_settings(plugins, &DotSettings::default())
+}
+
+pub fn plugin_graph_dot_with_settings(plugins: &RecordedPlugins, settings: &DotSettings) -> String {
+    let graph = DebugGraph::from_plugins(plugins);
+    DotExporter::new(settings).export_to_string(&graph)
+}
+
+impl DebugGraph {
+    /// Chains the top level plugins in registration order and connects
+    /// every plugin with the plugins it added while building.
+    pub fn from_plugins(plugins: &RecordedPlugins) -> DebugGraph {
+        let id = |index: usize| format!("plugin_{}", index);
+
+        let nodes = plugins
+            .plugins
+            .iter()
+            .enumerate()
+            .map(|(index, plugin)| DebugNode {
+                id: id(index),
+                name: utils::short_name(&plugin.name),
+                type_name: plugin.name.clone(),
+                kind: if plugin.group {
+                    NodeKind::PluginGroup
+                } else {
+                    NodeKind::Plugin
+                },
+                inputs: Vec::new(),
+                outputs: Vec::new(),
+                cluster: None,
+                details: Vec::new(),
+                access: Vec::new(),
+                dimmed: false,
+            })
+            .collect();
+
+        let mut edges = Vec::new();
+        let mut previous = None;
+        for (index, plugin) in plugins.plugins.iter().enumerate() {
+            match plugin.parent {
+                Some(parent) => {
+                    let mut edge = DebugEdge::new(id(parent), id(index), EdgeKind::Ordering);
+                    edge.label = Some("adds".to_string());
+                    edges.push(edge);
+                }
+                None => {
+                    if let Some(previous) = previous {
+                        edges.push(DebugEdge::new(id(previous), id(index), EdgeKind::Ordering));
+                    }
+                    previous = Some(index);
+                }
+            }
+        }
+
+        DebugGraph {
+            name: "Plugins".to_string(),
+            nodes,
+            edges,
+            clusters: Vec::new(),
+            ranks: Vec::new(),
+        }
+    }
+}
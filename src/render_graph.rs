@@ -1,113 +1,309 @@
 use crate::{
-    dot::{font_tag, html_escape, DotGraph},
+    cycles::{edge_nodes, find_cycles},
+    dot_exporter::{DotExporter, DotSettings, StatementCursor},
+    graph::{
+        is_bevy_internal, DebugEdge, DebugGraph, DebugNode, DebugSlot, EdgeKind, GraphExporter,
+        NodeKind,
+    },
+    mismatches::find_slot_mismatches,
     utils,
 };
-use bevy::render::render_graph::{Edge, NodeId, RenderGraph};
-use itertools::{EitherOrBoth, Itertools};
+use bevy::{
+    app::App,
+    ecs::world::World,
+    render::{
+        render_graph::{Edge, NodeId, NodeState, RenderGraph, ResourceSlot, ResourceSlots},
+        renderer::{RenderResourceContext, RenderResourceId},
+    },
+    utils::HashMap,
+};
+use std::io::{self, Write};
 
-pub fn render_graph_dot(graph: &RenderGraph) -> String {
-    let options = [("rankdir", "LR"), ("ranksep", "1.0")];
-    let mut dot = DotGraph::new("RenderGraph", &options);
+/// Maps the nodes of a [`RenderGraph`] to the identifiers used in the dot output.
+///
+/// Identifiers are derived from the node name (or short type name for unnamed nodes),
+/// with a `_2`, `_3`, ... suffix when multiple nodes sanitize to the same identifier.
+#[derive(Debug, Clone, Default)]
+pub struct RenderGraphIds {
+    ids: HashMap<NodeId, String>,
+    nodes: HashMap<String, NodeId>,
+}
+
+impl RenderGraphIds {
+    pub fn new(graph: &RenderGraph) -> RenderGraphIds {
+        let mut ids = RenderGraphIds::default();
+        for node in sorted_nodes(graph) {
+            let base = utils::sanitize_id(&display_name(node));
+
+            let mut id = base.clone();
+            let mut suffix = 1;
+            while ids.nodes.contains_key(&id) {
+                suffix += 1;
+                id = format!("{}_{}", base, suffix);
+            }
+
+            ids.nodes.insert(id.clone(), node.id);
+            ids.ids.insert(node.id, id);
+        }
+        ids
+    }
+
+    pub fn id(&self, node: &NodeId) -> Option<&str> {
+        self.ids.get(node).map(String::as_str)
+    }
+
+    pub fn node(&self, id: &str) -> Option<NodeId> {
+        self.nodes.get(id).copied()
+    }
 
-    // Convert to format fitting GraphViz node id requirements
-    let node_id = |id: &NodeId| format!("{}", id.uuid().as_u128());
-    let font = ("fontname", "Roboto");
-    let shape = ("shape", "plaintext");
-    let edge_color = ("color", "\"blue\"");
+    pub fn iter(&self) -> impl Iterator<Item = (&str, NodeId)> {
+        self.nodes.iter().map(|(id, node)| (id.as_str(), *node))
+    }
+}
 
-    dot.edge_attributes(&[font]).node_attributes(&[shape, font]);
+fn display_name(node: &NodeState) -> String {
+    match &node.name {
+        Some(name) => name.to_string(),
+        None => utils::short_name(node.type_name),
+    }
+}
 
+/// nodes in a deterministic order, so that ids and output are stable between runs
+pub(crate) fn sorted_nodes(graph: &RenderGraph) -> Vec<&NodeState> {
     let mut nodes: Vec<_> = graph.iter_nodes().collect();
-    nodes.sort_by_key(|node_state| &node_state.type_name);
+    nodes.sort_by(|a, b| (&a.name, a.type_name).cmp(&(&b.name, b.type_name)));
+    nodes
+}
 
-    for node in &nodes {
-        let name = node.name.as_deref().unwrap_or("<node>");
-        let type_name = utils::short_name(node.type_name);
+#[derive(Debug, Clone, Default)]
+pub struct RenderGraphSettings {
+    pub dot: DotSettings,
+    /// Hides the nodes whose type is defined by bevy (i.e. the path starts with `bevy_`).
+    /// Nodes connected through hidden nodes are connected directly instead.
+    /// Note that this also hides user-added instances of bevy node types, like a `PassNode`.
+    pub show_user_only: bool,
+    /// Draws multiple slot edges between the same nodes as a single edge listing the slots.
+    pub merge_slot_edges: bool,
+    /// Prefixes the node titles with their position in the execution order, e.g. `#07 main_pass`.
+    /// Nodes in cycles can't be executed and aren't numbered.
+    pub execution_order: bool,
+    /// Replaces the names of all nodes not defined by bevy, including generic bevy nodes of user types,
+    /// with stable hashes, see [`DebugGraph::redact`].
+    pub redact: bool,
+    /// Lines up the nodes running at the same depth of the graph, see [`DebugGraph::rank_by_execution_layers`].
+    pub rank_by_depth: bool,
+}
 
-        let inputs = node
-            .input_slots
-            .iter()
-            .enumerate()
-            .map(|(index, slot)| {
-                format!(
-                    "<TD PORT=\"{}\">{}: {}</TD>",
-                    html_escape(&format!("{}", index)),
-                    html_escape(&slot.info.name),
-                    html_escape(&format!("{:?}", slot.info.resource_type))
-                )
-            })
-            .collect::<Vec<_>>();
+pub fn render_graph_dot(graph: &RenderGraph) -> String {
+    render_graph_dot_with_settings(graph, &RenderGraphSettings::default())
+}
+
+pub fn render_graph_dot_with_settings(
+    graph: &RenderGraph,
+    settings: &RenderGraphSettings,
+) -> String {
+    let graph = debug_graph(graph, settings);
+    DotExporter::new(&settings.dot).export_to_string(&graph)
+}
+
+/// Looks up the [`RenderGraph`] resource of the app, `None` if the app has no render graph.
+/// This bevy version has no render sub-app, the render graph lives in the main world.
+pub fn render_graph_dot_from_app(app: &App) -> Option<String> {
+    render_graph_dot_from_world(&app.world)
+}
+
+pub fn render_graph_dot_from_world(world: &World) -> Option<String> {
+    let graph = world.get_resource::<RenderGraph>()?;
+    Some(render_graph_dot(graph))
+}
+
+/// Prints the render graph of the app to stdout, or logs a warning if it has none.
+pub fn print_render_graph(app: &App) {
+    match render_graph_dot_from_app(app) {
+        Some(dot) => println!("{}", dot),
+        None => bevy::log::warn!("the app has no render graph, is the `RenderPlugin` missing?"),
+    }
+}
 
-        let outputs = node
-            .output_slots
+/// Writes the statements into `writer` while they are generated instead of collecting them into a `String` first.
+pub fn render_graph_write<W: Write>(
+    graph: &RenderGraph,
+    settings: &RenderGraphSettings,
+    writer: &mut W,
+) -> io::Result<()> {
+    let graph = debug_graph(graph, settings);
+    DotExporter::new(&settings.dot).export(&graph, writer)
+}
+
+/// The statements of the dot output one at a time, generated when they are requested, see [`DotExporter::statements`].
+/// Placed between `digraph RenderGraph {` and `}`, they can be interleaved with your own statements.
+pub fn iter_dot_stmts(graph: &RenderGraph) -> impl Iterator<Item = String> {
+    iter_dot_stmts_with_settings(graph, &RenderGraphSettings::default())
+}
+
+pub fn iter_dot_stmts_with_settings(
+    graph: &RenderGraph,
+    settings: &RenderGraphSettings,
+) -> impl Iterator<Item = String> {
+    let graph = debug_graph(graph, settings);
+    let settings = settings.dot.clone();
+    let mut cursor = StatementCursor::default();
+    std::iter::from_fn(move || DotExporter::new(&settings).next_statement(&graph, &mut cursor))
+}
+
+/// the extracted graph, with the filters of the settings applied
+fn debug_graph(graph: &RenderGraph, settings: &RenderGraphSettings) -> DebugGraph {
+    transform(DebugGraph::from_render_graph(graph), settings)
+}
+
+pub(crate) fn transform(mut graph: DebugGraph, settings: &RenderGraphSettings) -> DebugGraph {
+    if settings.show_user_only {
+        graph.retain_nodes(|node| !is_bevy_internal(node));
+    }
+    if settings.merge_slot_edges {
+        graph.merge_slot_edges();
+    }
+    if settings.redact {
+        graph.redact();
+    }
+    if settings.execution_order {
+        graph.number_in_execution_order();
+    }
+    if settings.rank_by_depth {
+        graph.rank_by_execution_layers();
+    }
+    graph
+}
+
+/// Like [`render_graph_dot_with_settings`], additionally showing the resources bound to the slots,
+/// see [`DebugGraph::add_bound_resources`]. Only buffers are described in detail,
+/// textures and samplers are only identified by their id.
+pub fn render_graph_dot_with_bound_resources(
+    graph: &RenderGraph,
+    render_resource_context: &dyn RenderResourceContext,
+    settings: &RenderGraphSettings,
+) -> String {
+    let mut debug_graph = DebugGraph::from_render_graph(graph);
+    debug_graph.add_bound_resources(graph, render_resource_context);
+    let debug_graph = transform(debug_graph, settings);
+    DotExporter::new(&settings.dot).export_to_string(&debug_graph)
+}
+
+fn slots(slots: &ResourceSlots) -> Vec<DebugSlot> {
+    slots
+        .iter()
+        .map(|slot| DebugSlot {
+            name: slot.info.name.to_string(),
+            resource_type: format!("{:?}", slot.info.resource_type),
+            resource: None,
+        })
+        .collect()
+}
+
+impl DebugGraph {
+    pub fn from_render_graph(graph: &RenderGraph) -> DebugGraph {
+        let ids = RenderGraphIds::new(graph);
+        let node_id = |id: &NodeId| ids.id(id).expect("node is part of the graph").to_string();
+
+        let cycles = find_cycles(graph);
+        let in_cycle = |edge: &Edge| cycles.iter().any(|cycle| cycle.contains_edge(edge));
+        let mismatches = find_slot_mismatches(graph);
+        let is_mismatched = |edge: &Edge| mismatches.iter().any(|mismatch| &mismatch.edge == edge);
+
+        let nodes = sorted_nodes(graph);
+
+        let debug_nodes = nodes
             .iter()
-            .enumerate()
-            .map(|(index, slot)| {
-                format!(
-                    "<TD PORT=\"{}\">{}: {}</TD>",
-                    html_escape(&format!("{}", index)),
-                    html_escape(&slot.info.name),
-                    html_escape(&format!("{:?}", slot.info.resource_type))
-                )
+            .map(|node| DebugNode {
+                id: node_id(&node.id),
+                name: node.name.as_deref().unwrap_or("<node>").to_string(),
+                type_name: node.type_name.to_string(),
+                kind: NodeKind::RenderNode,
+                inputs: slots(&node.input_slots),
+                outputs: slots(&node.output_slots),
+                cluster: None,
+                details: vec![node.id.uuid().to_string()],
+                access: Vec::new(),
+                dimmed: false,
             })
-            .collect::<Vec<_>>();
+            .collect();
 
-        let slots = inputs
+        let edges = nodes
             .iter()
-            .zip_longest(outputs.iter())
-            .map(|pair| match pair {
-                EitherOrBoth::Both(input, output) => format!("<TR>{}{}</TR>", input, output),
-                EitherOrBoth::Left(input) => {
-                    format!("<TR>{}<TD BORDER=\"0\">&nbsp;</TD></TR>", input)
-                }
-                EitherOrBoth::Right(output) => {
-                    format!("<TR><TD BORDER=\"0\">&nbsp;</TD>{}</TR>", output)
+            .flat_map(|node| &node.edges.input_edges)
+            .map(|edge| {
+                let (from, to) = edge_nodes(edge);
+                let kind = match *edge {
+                    Edge::SlotEdge {
+                        input_index,
+                        output_index,
+                        ..
+                    } => EdgeKind::Slot {
+                        output_index,
+                        input_index,
+                    },
+                    Edge::NodeEdge { .. } => EdgeKind::Node,
+                };
+                DebugEdge {
+                    highlighted: in_cycle(edge) || is_mismatched(edge),
+                    ..DebugEdge::new(node_id(&from), node_id(&to), kind)
                 }
             })
-            .collect::<String>();
+            .collect();
 
-        let label = format!(
-            "<<TABLE STYLE=\"rounded\"><TR><TD PORT=\"title\" BORDER=\"0\" COLSPAN=\"2\">{}<BR/>{}</TD></TR>{}</TABLE>>",
-            html_escape(name),
-            font_tag(&type_name, "red", 10),
-            slots,
-        );
+        DebugGraph {
+            name: "RenderGraph".to_string(),
+            nodes: debug_nodes,
+            edges,
+            ..Default::default()
+        }
+    }
+}
 
-        dot.add_node(&node_id(&node.id), &[("label", &label)]);
+/// describes the resource bound to the slot, as far as the render resource context knows about it
+fn bound_resource(
+    slot: &ResourceSlot,
+    render_resource_context: &dyn RenderResourceContext,
+) -> Option<String> {
+    match slot.resource? {
+        RenderResourceId::Buffer(buffer) => {
+            let info = render_resource_context.get_buffer_info(buffer)?;
+            Some(format!("{} bytes, {:?}", info.size, info.buffer_usage))
+        }
+        // the context doesn't expose texture or sampler descriptors, and the nodes creating them
+        // keep the descriptors private, so there is no format, size or sample count to show
+        RenderResourceId::Texture(texture) => Some(format!("{:?}", texture)),
+        RenderResourceId::Sampler(sampler) => Some(format!("{:?}", sampler)),
     }
+}
 
-    for node in graph.iter_nodes() {
-        for edge in &node.edges.input_edges {
-            match edge {
-                Edge::SlotEdge {
-                    input_node,
-                    input_index,
-                    output_node,
-                    output_index,
-                } => {
-                    dot.add_edge(
-                        &node_id(output_node),
-                        Some(&format!("{}:e", output_index)),
-                        &node_id(input_node),
-                        Some(&format!("{}:w", input_index)),
-                        &[edge_color],
-                    );
-                }
-                Edge::NodeEdge {
-                    input_node,
-                    output_node,
-                } => {
-                    dot.add_edge(
-                        &node_id(output_node),
-                        Some("title:e"),
-                        &node_id(input_node),
-                        Some("title:w"),
-                        &[],
-                    );
-                }
+impl DebugGraph {
+    /// Records the resources bound to the slots during the last execution of the graph.
+    /// Buffers are described by their size and usage. Textures and samplers are only identified by their id,
+    /// as this bevy version doesn't expose their descriptors, so their format, size and sample count are unknown.
+    pub fn add_bound_resources(
+        &mut self,
+        graph: &RenderGraph,
+        render_resource_context: &dyn RenderResourceContext,
+    ) {
+        let ids = RenderGraphIds::new(graph);
+        for node_state in graph.iter_nodes() {
+            let id = match ids.id(&node_state.id) {
+                Some(id) => id,
+                None => continue,
+            };
+            let node = match self.nodes.iter_mut().find(|node| node.id == id) {
+                Some(node) => node,
+                None => continue,
+            };
+            let slots = node
+                .inputs
+                .iter_mut()
+                .zip(node_state.input_slots.iter())
+                .chain(node.outputs.iter_mut().zip(node_state.output_slots.iter()));
+            for (debug_slot, slot) in slots {
+                debug_slot.resource = bound_resource(slot, render_resource_context);
             }
         }
     }
-
-    dot.finish()
 }
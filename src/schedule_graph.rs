@@ -0,0 +1,822 @@
+use crate::{
+    dot_exporter::{DotExporter, DotSettings},
+    graph::{
+        is_bevy_internal, ClusterKind, ComponentAccess, DebugCluster, DebugEdge, DebugGraph,
+        DebugNode, EdgeKind, GraphExporter, NodeKind,
+    },
+    utils,
+};
+use bevy::{
+    app::App,
+    ecs::{
+        component::ComponentId,
+        query::Access,
+        schedule::{
+            BoxedAmbiguitySetLabel, BoxedSystemLabel, Schedule, Stage, StageLabel, SystemContainer,
+            SystemStage,
+        },
+        world::World,
+    },
+    utils::{HashMap, HashSet},
+};
+use std::{
+    borrow::Cow,
+    io::{self, Write},
+};
+
+#[derive(Default, Debug, Clone)]
+pub struct ScheduleGraphSettings {
+    pub dot: DotSettings,
+    /// fixed timestep in seconds of the run criteria driving a stage, keyed by stage name
+    pub fixed_timesteps: HashMap<String, f64>,
+    /// Groups parallel systems of the same set which can run at the same time into the same rank and
+    /// connects unordered systems with conflicting access, which can't.
+    /// Access is only known once the schedule has been initialized, i.e. has run once.
+    pub parallelism: bool,
+    /// Hides the systems defined by bevy (i.e. the function path starts with `bevy_`).
+    /// Systems ordered through hidden systems are connected directly instead.
+    pub show_user_only: bool,
+    /// Adds a barrier node to every stage where the `Commands` of its parallel systems are applied,
+    /// after which spawned entities and inserted components become visible.
+    pub sync_points: bool,
+    /// Component names used to label access, captured by [`ScheduleGraphSettings::with_world`].
+    pub component_names: Vec<(ComponentId, String)>,
+    /// Connects the systems writing a component with the systems reading it later in the frame,
+    /// i.e. ordered after the writer in the same stage or in a later stage.
+    /// This is read-after-write, not change detection: the schedule doesn't expose query filters,
+    /// so it can't tell which readers use `Changed<T>` or `Added<T>`.
+    /// Requires [`ScheduleGraphSettings::component_names`] and access, which is only known once the schedule has run.
+    pub read_after_write: bool,
+    /// Lists the components each system accesses in its node, marking mutable access.
+    /// The schedule only exposes the combined access of all queries of a system, not the individual queries or filters.
+    /// Requires [`ScheduleGraphSettings::component_names`].
+    pub system_access: bool,
+    /// Groups the systems of every ambiguity set, whose ambiguities were accepted intentionally, into a shaded cluster.
+    /// Systems in multiple ambiguity sets are grouped with the first one.
+    pub ambiguity_sets: bool,
+    /// Replaces the names of all systems not defined by bevy with stable hashes, see [`DebugGraph::redact`].
+    /// Stage names and labels of ambiguity sets are kept.
+    pub redact: bool,
+    /// Hides the edges which only order systems, like `.before()` and `.after()` constraints,
+    /// to only show which systems feed data into each other. The edges of [`ScheduleGraphSettings::sync_points`] are kept.
+    pub hide_ordering_edges: bool,
+    /// Hides the edges along which data flows, i.e. chains and [`ScheduleGraphSettings::read_after_write`],
+    /// to only show which systems order each other.
+    pub hide_data_edges: bool,
+}
+
+impl ScheduleGraphSettings {
+    /// Annotates the stage with the step of the `FixedTimestep` run criteria driving it.
+    /// Run criteria aren't exposed by the schedule, so they need to be specified here.
+    pub fn with_fixed_timestep(mut self, stage: impl StageLabel, step: f64) -> Self {
+        self.fixed_timesteps.insert(stage_name(&stage), step);
+        self
+    }
+
+    /// Captures the component names of the world, which system access is identified by.
+    pub fn with_world(mut self, world: &World) -> Self {
+        let components = world.components();
+        self.component_names = (0..components.len())
+            .map(ComponentId::new)
+            .filter_map(|id| Some((id, utils::short_name(components.get_info(id)?.name()))))
+            .collect();
+        self
+    }
+}
+
+pub fn schedule_graph_dot(schedule: &Schedule) -> String {
+    schedule_graph_dot_with_settings(schedule, &ScheduleGraphSettings::default())
+}
+
+pub fn schedule_graph_dot_with_settings(
+    schedule: &Schedule,
+    settings: &ScheduleGraphSettings,
+) -> String {
+    let graph = DebugGraph::from_schedule(schedule, settings);
+    DotExporter::new(&settings.dot).export_to_string(&graph)
+}
+
+/// Prints the schedule of the app to stdout.
+pub fn print_schedule(app: &App) {
+    println!("{}", schedule_graph_dot(&app.schedule));
+}
+
+/// Writes the statements into `writer` while they are generated instead of collecting them into a `String` first.
+pub fn schedule_graph_write<W: Write>(
+    schedule: &Schedule,
+    settings: &ScheduleGraphSettings,
+    writer: &mut W,
+) -> io::Result<()> {
+    let graph = DebugGraph::from_schedule(schedule, settings);
+    DotExporter::new(&settings.dot).export(&graph, writer)
+}
+
+/// Only the stage with the given label, e.g. `CoreStage::PostUpdate`, as the whole schedule is often too large to lay out nicely.
+/// `None` if the app has no such stage.
+pub fn schedule_graph_dot_for(app: &App, stage_label: impl StageLabel) -> Option<String> {
+    stage_graph_dot_with_settings(
+        &app.schedule,
+        &stage_label,
+        &ScheduleGraphSettings::default(),
+    )
+}
+
+pub fn stage_graph_dot_with_settings(
+    schedule: &Schedule,
+    stage_label: &dyn StageLabel,
+    settings: &ScheduleGraphSettings,
+) -> Option<String> {
+    let graph = DebugGraph::from_stage(schedule, stage_label, settings)?;
+    Some(DotExporter::new(&settings.dot).export_to_string(&graph))
+}
+
+impl DebugGraph {
+    /// Settings other than [`ScheduleGraphSettings::dot`] affect what is extracted.
+    pub fn from_schedule(schedule: &Schedule, settings: &ScheduleGraphSettings) -> DebugGraph {
+        let mut graph = DebugGraph {
+            name: "Schedule".to_string(),
+            ..Default::default()
+        };
+        let mut accesses = Vec::new();
+        add_schedule(
+            &mut graph,
+            schedule,
+            "schedule",
+            None,
+            settings,
+            &mut accesses,
+        );
+        finish_schedule_graph(&mut graph, &accesses, settings);
+        graph
+    }
+
+    /// Only the stage with the given label, which can also be part of a nested schedule.
+    /// `None` if the schedule has no such stage.
+    pub fn from_stage(
+        schedule: &Schedule,
+        stage_label: &dyn StageLabel,
+        settings: &ScheduleGraphSettings,
+    ) -> Option<DebugGraph> {
+        let name = stage_name(stage_label);
+        let (label, stage, schedule_id) = find_stage(schedule, "schedule".to_string(), &name)?;
+
+        let mut graph = DebugGraph {
+            name,
+            ..Default::default()
+        };
+        let mut accesses = Vec::new();
+        add_stage(
+            &mut graph,
+            label,
+            stage,
+            &schedule_id,
+            None,
+            settings,
+            &mut accesses,
+        );
+        finish_schedule_graph(&mut graph, &accesses, settings);
+        Some(graph)
+    }
+}
+
+fn finish_schedule_graph(
+    graph: &mut DebugGraph,
+    accesses: &[SystemAccess],
+    settings: &ScheduleGraphSettings,
+) {
+    if settings.read_after_write {
+        add_read_after_write(graph, accesses, &settings.component_names);
+    }
+    if settings.show_user_only {
+        graph.retain_nodes(|node| !is_bevy_internal(node));
+    }
+    if settings.redact {
+        graph.redact();
+    }
+    // sync points are only placed by their ordering edges, so those are always kept
+    let sync_points: HashSet<String> = graph
+        .nodes
+        .iter()
+        .filter(|node| node.kind == NodeKind::SyncPoint)
+        .map(|node| node.id.clone())
+        .collect();
+    let touches_sync_point =
+        |edge: &DebugEdge| sync_points.contains(&edge.from) || sync_points.contains(&edge.to);
+    graph.edges.retain(|edge| match edge.kind {
+        EdgeKind::Ordering => !settings.hide_ordering_edges || touches_sync_point(edge),
+        _ if edge.kind.is_data_flow() => !settings.hide_data_edges,
+        _ => true,
+    });
+}
+
+/// Searches nested schedules as well, returning the id of the schedule containing the stage.
+fn find_stage<'a>(
+    schedule: &'a Schedule,
+    schedule_id: String,
+    name: &str,
+) -> Option<(&'a dyn StageLabel, &'a dyn Stage, String)> {
+    for (label, stage) in schedule.iter_stages() {
+        let stage_id = utils::sanitize_id(&format!("{}_{}", schedule_id, stage_name(label)));
+        if stage_name(label) == name {
+            return Some((label, stage, schedule_id));
+        }
+        if let Some(found) = stage
+            .downcast_ref::<Schedule>()
+            .and_then(|nested| find_stage(nested, stage_id, name))
+        {
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// the groups of systems of a `SystemStage`, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    ExclusiveAtStart,
+    Parallel,
+    ExclusiveBeforeCommands,
+    /// runs after the commands of the parallel systems have been applied
+    ExclusiveAtEnd,
+}
+
+/// the access of a system, with what runs before it to find reads after writes
+struct SystemAccess<'a> {
+    id: String,
+    access: &'a Access<ComponentId>,
+    /// position of the stage in execution order
+    stage: usize,
+    /// ids of the systems of the same stage (transitively) ordered before it
+    after: Vec<String>,
+}
+
+struct SystemNode<'a> {
+    name: Cow<'static, str>,
+    labels: &'a [BoxedSystemLabel],
+    before: &'a [BoxedSystemLabel],
+    after: &'a [BoxedSystemLabel],
+    ambiguity_sets: &'a [BoxedAmbiguitySetLabel],
+    /// `None` for exclusive systems
+    access: Option<&'a Access<ComponentId>>,
+    phase: Phase,
+    /// index of the `SystemSet` the system was added with, `0` for systems added on their own
+    system_set: usize,
+}
+
+impl SystemNode<'_> {
+    fn is_parallel(&self) -> bool {
+        self.phase == Phase::Parallel
+    }
+
+    /// the constraints ordering `self` before `other`, e.g. `before(Label)` on `self`
+    fn ordering_constraints(&self, other: &SystemNode) -> Vec<String> {
+        let before = self
+            .before
+            .iter()
+            .filter(|label| other.labels.contains(label))
+            .map(|label| format!("before({:?})", label));
+        let after = other
+            .after
+            .iter()
+            .filter(|label| self.labels.contains(label))
+            .map(|label| format!("after({:?})", label));
+        before.chain(after).collect()
+    }
+
+    fn runs_before(&self, other: &SystemNode) -> bool {
+        !self.ordering_constraints(other).is_empty()
+    }
+
+    /// systems without known access are considered compatible
+    fn is_compatible(&self, other: &SystemNode) -> bool {
+        match (self.access, other.access) {
+            (Some(a), Some(b)) => a.is_compatible(b),
+            _ => true,
+        }
+    }
+}
+
+fn system_nodes(
+    systems: &[impl SystemContainer],
+    phase: Phase,
+) -> impl Iterator<Item = SystemNode<'_>> {
+    systems.iter().map(move |system| SystemNode {
+        name: system.name(),
+        labels: system.labels(),
+        before: system.before(),
+        after: system.after(),
+        ambiguity_sets: system.ambiguity_sets(),
+        access: system.component_access(),
+        phase,
+        system_set: system.system_set(),
+    })
+}
+
+pub(crate) fn stage_name(label: &dyn StageLabel) -> String {
+    // string labels are debug printed with quotes
+    format!("{:?}", label).trim_matches('"').to_string()
+}
+
+/// `accesses` collects the access of every system, in the order the stages run
+fn add_schedule<'a>(
+    graph: &mut DebugGraph,
+    schedule: &'a Schedule,
+    schedule_id: &str,
+    parent: Option<&str>,
+    settings: &ScheduleGraphSettings,
+    accesses: &mut Vec<SystemAccess<'a>>,
+) {
+    let mut previous_stage: Option<String> = None;
+
+    for (stage_label, stage) in schedule.iter_stages() {
+        let stage_id = add_stage(
+            graph,
+            stage_label,
+            stage,
+            schedule_id,
+            parent,
+            settings,
+            accesses,
+        );
+
+        // rank the stage clusters in execution order by connecting their boundaries
+        if let Some(previous) = previous_stage {
+            graph.edges.push(DebugEdge::new(
+                previous,
+                stage_id.clone(),
+                EdgeKind::ClusterOrder,
+            ));
+        }
+        previous_stage = Some(stage_id);
+    }
+}
+
+/// Adds the cluster of the stage and returns its id.
+fn add_stage<'a>(
+    graph: &mut DebugGraph,
+    stage_label: &dyn StageLabel,
+    stage: &'a dyn Stage,
+    schedule_id: &str,
+    parent: Option<&str>,
+    settings: &ScheduleGraphSettings,
+    accesses: &mut Vec<SystemAccess<'a>>,
+) -> String {
+    let name = stage_name(stage_label);
+    let stage_id = utils::sanitize_id(&format!("{}_{}", schedule_id, name));
+
+    let nested_schedule = stage.downcast_ref::<Schedule>();
+    graph.clusters.push(DebugCluster {
+        id: stage_id.clone(),
+        label: name.clone(),
+        kind: match nested_schedule {
+            Some(_) => ClusterKind::Schedule,
+            None => ClusterKind::Stage,
+        },
+        parent: parent.map(str::to_string),
+        fixed_timestep: settings.fixed_timesteps.get(&name).copied(),
+    });
+
+    if let Some(system_stage) = stage.downcast_ref::<SystemStage>() {
+        let systems: Vec<_> = std::iter::empty()
+            .chain(system_nodes(
+                system_stage.exclusive_at_start_systems(),
+                Phase::ExclusiveAtStart,
+            ))
+            .chain(system_nodes(
+                system_stage.parallel_systems(),
+                Phase::Parallel,
+            ))
+            .chain(system_nodes(
+                system_stage.exclusive_before_commands_systems(),
+                Phase::ExclusiveBeforeCommands,
+            ))
+            .chain(system_nodes(
+                system_stage.exclusive_at_end_systems(),
+                Phase::ExclusiveAtEnd,
+            ))
+            .collect();
+
+        let stage = accesses.last().map_or(0, |access| access.stage + 1);
+        let orderings: Vec<(usize, usize)> = (0..systems.len())
+            .flat_map(|a| (0..systems.len()).map(move |b| (a, b)))
+            .filter(|&(a, b)| systems[a].runs_before(&systems[b]))
+            .collect();
+        let ordered = transitive_orderings(systems.len(), &orderings);
+        let system_id = |index: usize| format!("{}_{}", stage_id, index);
+        accesses.extend(systems.iter().enumerate().filter_map(|(index, system)| {
+            Some(SystemAccess {
+                id: system_id(index),
+                access: system.access?,
+                stage,
+                after: (0..systems.len())
+                    .filter(|&before| ordered[before][index])
+                    .map(system_id)
+                    .collect(),
+            })
+        }));
+        add_systems(graph, &stage_id, &systems, &orderings, &ordered, settings);
+    } else if let Some(schedule) = nested_schedule {
+        add_schedule(
+            graph,
+            schedule,
+            &stage_id,
+            Some(&stage_id),
+            settings,
+            accesses,
+        );
+    } else {
+        graph.nodes.push(DebugNode {
+            id: format!("{}_unknown", stage_id),
+            name: "<unknown stage type>".to_string(),
+            type_name: String::new(),
+            kind: NodeKind::Placeholder,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            cluster: Some(stage_id.clone()),
+            details: Vec::new(),
+            access: Vec::new(),
+            dimmed: false,
+        });
+    }
+
+    stage_id
+}
+
+/// Splits the name of a chained system like `Chain(a, Chain(b, c))` into the names of its systems.
+/// Other names are returned as they are.
+fn chain_parts(name: &str) -> Vec<&str> {
+    let inner = match name
+        .strip_prefix("Chain(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        Some(inner) => inner,
+        None => return vec![name],
+    };
+
+    // the names can contain generics and nested chains, so only split at the top level
+    let mut depth = 0;
+    let mut previous = None;
+    let split = inner.char_indices().find(|&(_, c)| {
+        match c {
+            '(' | '<' => depth += 1,
+            // the `>` of `->` in fn pointer types doesn't close anything
+            '>' if previous == Some('-') => {}
+            ')' | '>' => depth -= 1,
+            _ => {}
+        }
+        previous = Some(c);
+        c == ',' && depth == 0
+    });
+    match split {
+        Some((index, _)) => {
+            let mut parts = chain_parts(inner[..index].trim());
+            parts.extend(chain_parts(inner[index + 1..].trim()));
+            parts
+        }
+        None => vec![name],
+    }
+}
+
+fn chain_part_id(stage_id: &str, index: usize, part_index: usize) -> String {
+    match part_index {
+        0 => format!("{}_{}", stage_id, index),
+        _ => format!("{}_{}_chain_{}", stage_id, index, part_index),
+    }
+}
+
+/// the node of the last system of a chain, which the systems ordered after the chain follow
+fn system_exit_id(stage_id: &str, index: usize, system: &SystemNode) -> String {
+    chain_part_id(stage_id, index, chain_parts(&system.name).len() - 1)
+}
+
+/// `orderings` are the direct orderings between the systems as `(before, after)`,
+/// `ordered[a][b]` whether `a` (transitively) runs before `b`
+fn add_systems(
+    graph: &mut DebugGraph,
+    stage_id: &str,
+    systems: &[SystemNode],
+    orderings: &[(usize, usize)],
+    ordered: &[Vec<bool>],
+    settings: &ScheduleGraphSettings,
+) {
+    let system_id = |index: usize| format!("{}_{}", stage_id, index);
+
+    let mut system_sets: Vec<usize> = systems.iter().map(|system| system.system_set).collect();
+    system_sets.sort_unstable();
+    system_sets.dedup();
+
+    for system_set in system_sets.into_iter().filter(|&set| set != 0) {
+        graph.clusters.push(DebugCluster {
+            id: format!("{}_set_{}", stage_id, system_set),
+            label: format!("SystemSet {}", system_set),
+            kind: ClusterKind::SystemSet,
+            parent: Some(stage_id.to_string()),
+            fixed_timestep: None,
+        });
+    }
+
+    let set_cluster = |system_set: usize| match system_set {
+        0 => stage_id.to_string(),
+        system_set => format!("{}_set_{}", stage_id, system_set),
+    };
+    let ambiguity_cluster = |system: &SystemNode| {
+        let ambiguity_set = system.ambiguity_sets.first()?;
+        let label = format!("{:?}", ambiguity_set);
+        let id = utils::sanitize_id(&format!(
+            "{}_ambiguity_{}",
+            set_cluster(system.system_set),
+            label
+        ));
+        Some((id, label))
+    };
+
+    if settings.ambiguity_sets {
+        let mut added = Vec::new();
+        for system in systems {
+            if let Some((id, label)) = ambiguity_cluster(system) {
+                if !added.contains(&id) {
+                    added.push(id.clone());
+                    graph.clusters.push(DebugCluster {
+                        id,
+                        label: format!("ambiguity set {}", label),
+                        kind: ClusterKind::AmbiguitySet,
+                        parent: Some(set_cluster(system.system_set)),
+                        fixed_timestep: None,
+                    });
+                }
+            }
+        }
+    }
+
+    for (index, system) in systems.iter().enumerate() {
+        let cluster = match ambiguity_cluster(system) {
+            Some((id, _)) if settings.ambiguity_sets => id,
+            _ => set_cluster(system.system_set),
+        };
+
+        // the systems of a chain are drawn one after another, the first one representing the whole chain
+        let parts = chain_parts(&system.name);
+        for (part_index, part) in parts.iter().enumerate() {
+            graph.nodes.push(DebugNode {
+                id: chain_part_id(stage_id, index, part_index),
+                name: utils::short_name(part),
+                type_name: part.to_string(),
+                kind: if system.is_parallel() {
+                    NodeKind::System
+                } else {
+                    NodeKind::ExclusiveSystem
+                },
+                inputs: Vec::new(),
+                outputs: Vec::new(),
+                cluster: Some(cluster.clone()),
+                details: Vec::new(),
+                access: match (part_index, settings.system_access, system.access) {
+                    (0, true, Some(access)) => component_access(access, &settings.component_names),
+                    _ => Vec::new(),
+                },
+                dimmed: false,
+            });
+            if part_index > 0 {
+                graph.edges.push(DebugEdge::new(
+                    chain_part_id(stage_id, index, part_index - 1),
+                    chain_part_id(stage_id, index, part_index),
+                    EdgeKind::Chain,
+                ));
+            }
+        }
+    }
+
+    for &(before, after) in orderings {
+        let constraints = systems[before].ordering_constraints(&systems[after]);
+        graph.edges.push(DebugEdge {
+            label: Some(constraints.join("\n")),
+            ..DebugEdge::new(
+                system_exit_id(stage_id, before, &systems[before]),
+                system_id(after),
+                EdgeKind::Ordering,
+            )
+        });
+    }
+
+    if settings.sync_points && systems.iter().any(|system| system.is_parallel()) {
+        add_sync_point(graph, stage_id, systems, orderings);
+    }
+
+    if settings.parallelism {
+        // ranks can't span clusters, so the layers are split by system set and ambiguity set
+        for layer in parallel_layers(systems, orderings) {
+            let mut ranks: Vec<(Option<String>, Vec<String>)> = Vec::new();
+            for id in layer.iter().map(|&index| system_id(index)) {
+                let cluster = graph.node(&id).and_then(|node| node.cluster.clone());
+                match ranks.iter_mut().find(|(other, _)| *other == cluster) {
+                    Some((_, rank)) => rank.push(id),
+                    None => ranks.push((cluster, vec![id])),
+                }
+            }
+            graph.ranks.extend(ranks.into_iter().map(|(_, rank)| rank));
+        }
+
+        for a in 0..systems.len() {
+            for b in a + 1..systems.len() {
+                let unordered = !ordered[a][b] && !ordered[b][a];
+                let both_parallel = systems[a].is_parallel() && systems[b].is_parallel();
+                if unordered && both_parallel && !systems[a].is_compatible(&systems[b]) {
+                    graph.edges.push(DebugEdge::new(
+                        system_id(a),
+                        system_id(b),
+                        EdgeKind::Conflict,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// The commands of the parallel systems are applied after them and the exclusive systems running
+/// before commands, but before the exclusive systems running at the end of the stage.
+fn add_sync_point(
+    graph: &mut DebugGraph,
+    stage_id: &str,
+    systems: &[SystemNode],
+    orderings: &[(usize, usize)],
+) {
+    let sync_id = format!("{}_apply_commands", stage_id);
+    let before_sync = |index: usize| {
+        matches!(
+            systems[index].phase,
+            Phase::Parallel | Phase::ExclusiveBeforeCommands
+        )
+    };
+    let after_sync = |index: usize| systems[index].phase == Phase::ExclusiveAtEnd;
+
+    graph.nodes.push(DebugNode {
+        id: sync_id.clone(),
+        name: "apply commands".to_string(),
+        type_name: String::new(),
+        kind: NodeKind::SyncPoint,
+        inputs: Vec::new(),
+        outputs: Vec::new(),
+        cluster: Some(stage_id.to_string()),
+        details: Vec::new(),
+        access: Vec::new(),
+        dimmed: false,
+    });
+
+    // only connect the last systems before and the first systems after the sync point,
+    // the others are ordered through them
+    for index in (0..systems.len()).filter(|&index| before_sync(index)) {
+        let has_successor = orderings
+            .iter()
+            .any(|&(before, after)| before == index && before_sync(after));
+        if !has_successor {
+            graph.edges.push(DebugEdge::new(
+                system_exit_id(stage_id, index, &systems[index]),
+                sync_id.clone(),
+                EdgeKind::Ordering,
+            ));
+        }
+    }
+    for index in (0..systems.len()).filter(|&index| after_sync(index)) {
+        let has_predecessor = orderings
+            .iter()
+            .any(|&(before, after)| after == index && after_sync(before));
+        if !has_predecessor {
+            graph.edges.push(DebugEdge::new(
+                sync_id.clone(),
+                format!("{}_{}", stage_id, index),
+                EdgeKind::Ordering,
+            ));
+        }
+    }
+}
+
+fn component_access(
+    access: &Access<ComponentId>,
+    component_names: &[(ComponentId, String)],
+) -> Vec<ComponentAccess> {
+    component_names
+        .iter()
+        .filter(|&&(id, _)| access.has_read(id) || access.has_write(id))
+        .map(|(id, name)| ComponentAccess {
+            component: name.clone(),
+            mutable: access.has_write(*id),
+        })
+        .collect()
+}
+
+/// connects every system writing a component to the systems reading it later in the frame
+fn add_read_after_write(
+    graph: &mut DebugGraph,
+    accesses: &[SystemAccess],
+    component_names: &[(ComponentId, String)],
+) {
+    for writer in accesses {
+        let later = accesses
+            .iter()
+            .filter(|reader| reader.stage > writer.stage || reader.after.contains(&writer.id));
+        for reader in later {
+            // write access includes read access, so systems writing the component are readers as well
+            let components: Vec<&str> = component_names
+                .iter()
+                .filter(|&&(id, _)| writer.access.has_write(id) && reader.access.has_read(id))
+                .map(|(_, name)| name.as_str())
+                .collect();
+            if !components.is_empty() {
+                graph.edges.push(DebugEdge {
+                    label: Some(components.join("\n")),
+                    ..DebugEdge::new(
+                        writer.id.clone(),
+                        reader.id.clone(),
+                        EdgeKind::ReadAfterWrite,
+                    )
+                });
+            }
+        }
+    }
+}
+
+/// `ordered[a][b]` is true if `a` (transitively) runs before `b`
+fn transitive_orderings(len: usize, orderings: &[(usize, usize)]) -> Vec<Vec<bool>> {
+    let mut ordered = vec![vec![false; len]; len];
+    for &(before, after) in orderings {
+        ordered[before][after] = true;
+    }
+    for k in 0..len {
+        let through_k = ordered[k].clone();
+        for row in ordered.iter_mut().filter(|row| row[k]) {
+            for (after, _) in through_k.iter().enumerate().filter(|(_, &ordered)| ordered) {
+                row[after] = true;
+            }
+        }
+    }
+    ordered
+}
+
+/// Greedily assigns the parallel systems to layers, placing every system in the first layer
+/// after all the systems it's ordered after which contains no system with conflicting access.
+/// The systems of a layer can run at the same time.
+fn parallel_layers(systems: &[SystemNode], orderings: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let mut layer_of: Vec<Option<usize>> = vec![None; systems.len()];
+    let mut layers: Vec<Vec<usize>> = Vec::new();
+
+    // bounded by the system count, so ordering cycles don't loop forever
+    for _ in 0..systems.len() {
+        for (index, system) in systems.iter().enumerate() {
+            if !system.is_parallel() || layer_of[index].is_some() {
+                continue;
+            }
+            let mut predecessors = orderings
+                .iter()
+                .filter(|&&(before, after)| after == index && systems[before].is_parallel())
+                .map(|&(before, _)| layer_of[before]);
+            let min_layer = match predecessors.try_fold(0, |min, layer| Some(min.max(layer? + 1))) {
+                Some(min_layer) => min_layer,
+                // a predecessor hasn't been placed yet
+                None => continue,
+            };
+
+            let layer = (min_layer..)
+                .find(|&layer| {
+                    layers
+                        .get(layer)
+                        .into_iter()
+                        .flatten()
+                        .all(|&member| systems[member].is_compatible(system))
+                })
+                .unwrap();
+            if layer >= layers.len() {
+                layers.resize_with(layer + 1, Vec::new);
+            }
+            layers[layer].push(index);
+            layer_of[index] = Some(layer);
+        }
+    }
+
+    layers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::chain_parts;
+
+    #[test]
+    fn chain_parts_split_at_top_level() {
+        assert_eq!(
+            chain_parts("Chain(a::first, Chain(b::second<(u8, u16)>, c::third))"),
+            vec!["a::first", "b::second<(u8, u16)>", "c::third"]
+        );
+    }
+
+    #[test]
+    fn chain_parts_ignore_fn_pointer_arrows() {
+        assert_eq!(
+            chain_parts("Chain(a::first<fn(u8) -> u16>, b::second)"),
+            vec!["a::first<fn(u8) -> u16>", "b::second"]
+        );
+    }
+
+    #[test]
+    fn chain_parts_keep_other_systems() {
+        assert_eq!(chain_parts("a::system<B>"), vec!["a::system<B>"]);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::graph::DebugGraph;
+use bevy::render::render_graph::RenderGraph;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt, fs,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+/// Version of the snapshot format, increased whenever the [`DebugGraph`] model changes incompatibly.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// A [`DebugGraph`] stored as json, so it can be rendered or diffed without running the app.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub graph: DebugGraph,
+}
+
+impl Snapshot {
+    pub fn new(graph: DebugGraph) -> Snapshot {
+        Snapshot {
+            version: SNAPSHOT_VERSION,
+            graph,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    Format(serde_json::Error),
+    /// the snapshot was written by an incompatible version of this crate
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(error) => write!(f, "failed to access snapshot: {}", error),
+            SnapshotError::Format(error) => write!(f, "invalid snapshot: {}", error),
+            SnapshotError::UnsupportedVersion(version) => write!(
+                f,
+                "snapshot has version {}, only version {} is supported",
+                version, SNAPSHOT_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<io::Error> for SnapshotError {
+    fn from(error: io::Error) -> Self {
+        SnapshotError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(error: serde_json::Error) -> Self {
+        SnapshotError::Format(error)
+    }
+}
+
+/// Saves the render graph to `path`, to be loaded again with [`load_snapshot`].
+pub fn save_snapshot(graph: &RenderGraph, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+    save_graph_snapshot(&DebugGraph::from_render_graph(graph), path)
+}
+
+/// Saves any extracted graph, e.g. from [`DebugGraph::from_schedule`].
+pub fn save_graph_snapshot(
+    graph: &DebugGraph,
+    path: impl AsRef<Path>,
+) -> Result<(), SnapshotError> {
+    // same format as `Snapshot`, borrowing the graph instead of cloning it
+    #[derive(Serialize)]
+    struct SnapshotRef<'a> {
+        version: u32,
+        graph: &'a DebugGraph,
+    }
+    let snapshot = SnapshotRef {
+        version: SNAPSHOT_VERSION,
+        graph,
+    };
+
+    let mut writer = BufWriter::new(fs::File::create(path)?);
+    serde_json::to_writer_pretty(&mut writer, &snapshot)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Loads a graph saved with [`save_snapshot`] or [`save_graph_snapshot`].
+/// Render it with any [`GraphExporter`](crate::GraphExporter), like the [`DotExporter`](crate::DotExporter).
+pub fn load_snapshot(path: impl AsRef<Path>) -> Result<DebugGraph, SnapshotError> {
+    let contents = fs::read_to_string(path)?;
+
+    // check the version before the rest, so old snapshots report a useful error
+    #[derive(Deserialize)]
+    struct Header {
+        version: u32,
+    }
+    let header: Header = serde_json::from_str(&contents)?;
+    if header.version != SNAPSHOT_VERSION {
+        return Err(SnapshotError::UnsupportedVersion(header.version));
+    }
+
+    let snapshot: Snapshot = serde_json::from_str(&contents)?;
+    Ok(snapshot.graph)
+}
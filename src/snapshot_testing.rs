@@ -0,0 +1,167 @@
+use std::{fs, path::Path};
+
+/// Environment variable which, when set, overwrites the golden files instead of comparing against them.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "UPDATE_SNAPSHOTS";
+
+/// Asserts that the render graph's dot output matches the golden file, relative to the crate root.
+///
+/// Panics if the file doesn't exist, unless `UPDATE_SNAPSHOTS` is set to create or overwrite it.
+/// ```ignore
+/// assert_render_graph_snapshot!(render_graph, "tests/render_graph.dot");
+/// ```
+#[macro_export]
+macro_rules! assert_render_graph_snapshot {
+    ($graph:expr, $path:expr) => {
+        $crate::assert_dot_snapshot(
+            &$crate::render_graph_dot(&$graph),
+            ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($path),
+        )
+    };
+}
+
+/// Like [`assert_render_graph_snapshot`], for the schedule graph.
+#[macro_export]
+macro_rules! assert_schedule_graph_snapshot {
+    ($schedule:expr, $path:expr) => {
+        $crate::assert_dot_snapshot(
+            &$crate::schedule_graph_dot(&$schedule),
+            ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($path),
+        )
+    };
+}
+
+/// Compares the normalized dot output with the golden file at `path`, see [`assert_render_graph_snapshot`].
+#[track_caller]
+pub fn assert_dot_snapshot(dot: &str, path: impl AsRef<Path>) {
+    let update = std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some();
+    compare_snapshot(dot, path.as_ref(), update);
+}
+
+#[track_caller]
+fn compare_snapshot(dot: &str, path: &Path, update: bool) {
+    let actual = normalize_dot(dot);
+
+    if update {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(path, &actual)
+            .unwrap_or_else(|error| panic!("failed to write {}: {}", path.display(), error));
+        return;
+    }
+    let expected = fs::read_to_string(path).unwrap_or_else(|error| {
+        panic!(
+            "failed to read the snapshot {}: {}\nrun with {}=1 to create it",
+            path.display(),
+            error,
+            UPDATE_SNAPSHOTS_VAR
+        )
+    });
+    let expected = normalize_dot(&expected);
+
+    if actual != expected {
+        let (line, (expected_line, actual_line)) = expected
+            .lines()
+            .chain(std::iter::repeat(""))
+            .zip(actual.lines().chain(std::iter::repeat("")))
+            .enumerate()
+            .find(|(_, (expected, actual))| expected != actual)
+            .unwrap();
+        panic!(
+            "graph doesn't match the snapshot {}, first difference in line {}:\n  expected: {}\n  actual:   {}\nrerun with {}=1 to update the snapshot",
+            path.display(),
+            line + 1,
+            expected_line,
+            actual_line,
+            UPDATE_SNAPSHOTS_VAR
+        );
+    }
+}
+
+/// Replaces uuids, which change between runs, with `<uuid>` and unifies line endings.
+pub fn normalize_dot(dot: &str) -> String {
+    let dot = dot.replace("\r\n", "\n");
+    let chars: Vec<char> = dot.chars().collect();
+
+    let mut normalized = String::with_capacity(dot.len());
+    let mut index = 0;
+    while index < chars.len() {
+        if is_uuid(&chars[index..]) {
+            normalized.push_str("<uuid>");
+            index += 36;
+        } else {
+            normalized.push(chars[index]);
+            index += 1;
+        }
+    }
+    normalized
+}
+
+/// whether the text starts with a uuid like `67e55044-10b1-426f-9247-bb680e5fe0c8`
+fn is_uuid(text: &[char]) -> bool {
+    text.len() >= 36
+        && text[..36].iter().enumerate().all(|(index, c)| match index {
+            8 | 13 | 18 | 23 => *c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a fresh directory for the golden files of a test
+    fn snapshot_dir(test: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir()
+            .join("bevy_mod_debugdump_snapshots")
+            .join(test);
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn normalize_dot_replaces_uuids() {
+        assert_eq!(
+            normalize_dot("\tnode_1 [label=\"67e55044-10b1-426f-9247-bb680e5fe0c8\"]"),
+            "\tnode_1 [label=\"<uuid>\"]"
+        );
+    }
+
+    #[test]
+    fn normalize_dot_unifies_line_endings() {
+        assert_eq!(normalize_dot("digraph {\r\n}\r\n"), "digraph {\n}\n");
+    }
+
+    #[test]
+    fn normalize_dot_keeps_text_resembling_uuids() {
+        let dot = "67e55044-10b1-426f-9247-bb680e5fe0c 67e55044_10b1_426f_9247_bb680e5fe0c8";
+        assert_eq!(normalize_dot(dot), dot);
+    }
+
+    #[test]
+    fn snapshot_is_written_and_then_compared() {
+        let path = snapshot_dir("written").join("graph.dot");
+        let dot = "digraph {\n\ta [label=\"67e55044-10b1-426f-9247-bb680e5fe0c8\"]\n}\n";
+
+        compare_snapshot(dot, &path, true);
+        assert_eq!(fs::read_to_string(&path).unwrap(), normalize_dot(dot));
+
+        // a different uuid still matches
+        compare_snapshot(&dot.replace("67e55044", "0123abcd"), &path, false);
+    }
+
+    #[test]
+    #[should_panic(expected = "run with UPDATE_SNAPSHOTS=1 to create it")]
+    fn missing_snapshot_panics() {
+        let path = snapshot_dir("missing").join("graph.dot");
+        compare_snapshot("digraph {\n}\n", &path, false);
+    }
+
+    #[test]
+    #[should_panic(expected = "first difference in line 2")]
+    fn snapshot_mismatch_panics() {
+        let path = snapshot_dir("mismatch").join("graph.dot");
+        compare_snapshot("digraph {\n\ta\n}\n", &path, true);
+        compare_snapshot("digraph {\n\tb\n}\n", &path, false);
+    }
+}
@@ -21,3 +21,30 @@ pub fn short_name(type_name: &str) -> String {
         }
     }
 }
+
+/// Replaces everything but ascii alphanumerics and `_`, so the result can be used as an identifier.
+pub fn sanitize_id(name: &str) -> String {
+    let mut id: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if id.is_empty() || id.starts_with(|c: char| c.is_ascii_digit()) {
+        id.insert(0, '_');
+    }
+    id
+}
+
+/// Links to the docs.rs search for the type in its crate, as the type path
+/// includes private modules and can't be turned into the path of the documentation page.
+pub fn docs_rs_url(type_name: &str, version: &str) -> Option<String> {
+    let without_generics = type_name.split('<').next().unwrap_or(type_name);
+    let crate_name = without_generics.split("::").next()?;
+    let name = without_generics.rsplit("::").next()?;
+    if crate_name == name {
+        return None;
+    }
+    Some(format!(
+        "https://docs.rs/{0}/{1}/{0}/?search={2}",
+        crate_name, version, name
+    ))
+}
@@ -0,0 +1,133 @@
+use crate::{
+    cycles::{find_cycles, RenderGraphCycle},
+    mismatches::{find_slot_mismatches, SlotMismatch},
+    render_graph::sorted_nodes,
+};
+use bevy::render::render_graph::{Edge, NodeId, RenderGraph};
+use std::fmt;
+
+/// A problem with the wiring of a render graph.
+#[derive(Debug, Clone)]
+pub enum GraphIssue {
+    /// an input slot without a slot edge, which has no value when the node runs
+    UnconnectedInput {
+        node: NodeId,
+        slot: String,
+    },
+    SlotMismatch(SlotMismatch),
+    /// the graph can't be executed
+    Cycle(RenderGraphCycle),
+    /// A node without any edges, in a graph with other nodes. It runs at an arbitrary point
+    /// and nothing uses what it renders, usually because an edge was forgotten.
+    UnreachableNode {
+        node: NodeId,
+    },
+}
+
+/// The issues found by [`validate`], grouped by their kind.
+#[derive(Debug, Clone, Default)]
+pub struct GraphReport {
+    pub issues: Vec<GraphIssue>,
+    node_names: Vec<(NodeId, String)>,
+}
+
+impl GraphReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    fn node_name(&self, id: NodeId) -> String {
+        self.node_names
+            .iter()
+            .find(|(node, _)| *node == id)
+            .map(|(_, name)| name.clone())
+            .unwrap_or_else(|| format!("{:?}", id))
+    }
+}
+
+/// Checks the render graph for issues without rendering it, so e.g. CI can fail on a broken graph.
+pub fn validate(graph: &RenderGraph) -> GraphReport {
+    let nodes = sorted_nodes(graph);
+    let mut issues = Vec::new();
+
+    for node in &nodes {
+        for (index, slot) in node.input_slots.iter().enumerate() {
+            let connected = node.edges.input_edges.iter().any(
+                |edge| matches!(edge, Edge::SlotEdge { input_index, .. } if *input_index == index),
+            );
+            if !connected {
+                issues.push(GraphIssue::UnconnectedInput {
+                    node: node.id,
+                    slot: slot.info.name.to_string(),
+                });
+            }
+        }
+    }
+
+    issues.extend(
+        find_slot_mismatches(graph)
+            .into_iter()
+            .map(GraphIssue::SlotMismatch),
+    );
+    issues.extend(find_cycles(graph).into_iter().map(GraphIssue::Cycle));
+
+    if nodes.len() > 1 {
+        issues.extend(
+            nodes
+                .iter()
+                .filter(|node| {
+                    node.edges.input_edges.is_empty() && node.edges.output_edges.is_empty()
+                })
+                .map(|node| GraphIssue::UnreachableNode { node: node.id }),
+        );
+    }
+
+    GraphReport {
+        issues,
+        node_names: nodes
+            .iter()
+            .map(|node| {
+                let name = node.name.as_deref().unwrap_or(node.type_name);
+                (node.id, name.to_string())
+            })
+            .collect(),
+    }
+}
+
+/// One issue per line.
+impl fmt::Display for GraphReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            match issue {
+                GraphIssue::UnconnectedInput { node, slot } => writeln!(
+                    f,
+                    "input slot `{}` of `{}` isn't connected",
+                    slot,
+                    self.node_name(*node)
+                )?,
+                GraphIssue::SlotMismatch(mismatch) => writeln!(
+                    f,
+                    "`{}` outputs a {:?} into a {:?} slot of `{}`",
+                    self.node_name(mismatch.output_node),
+                    mismatch.output_type,
+                    mismatch.input_type,
+                    self.node_name(mismatch.input_node)
+                )?,
+                GraphIssue::Cycle(cycle) => {
+                    let names: Vec<_> = cycle
+                        .nodes
+                        .iter()
+                        .map(|node| format!("`{}`", self.node_name(*node)))
+                        .collect();
+                    writeln!(f, "cycle between {}", names.join(", "))?
+                }
+                GraphIssue::UnreachableNode { node } => writeln!(
+                    f,
+                    "`{}` isn't connected to any other node",
+                    self.node_name(*node)
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
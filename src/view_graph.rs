@@ -0,0 +1,94 @@
+use crate::{
+    dot_exporter::DotExporter,
+    graph::{DebugGraph, GraphExporter, NodeCategory},
+    render_graph::{transform, RenderGraphSettings},
+};
+use bevy::{
+    ecs::{entity::Entity, world::World},
+    render::{camera::Camera, render_graph::RenderGraph},
+    utils::HashSet,
+};
+
+/// Only the part of the render graph executed for the camera: its camera node, the nodes following it
+/// and the nodes those depend on, leaving out other cameras. The camera node is annotated with the window
+/// the camera renders to.
+///
+/// This bevy version has no render sub graphs and the camera nodes don't expose which camera they drive,
+/// so the node is found by comparing the camera name with the node names, ignoring case and `_`,
+/// e.g. the `Camera3d` camera is driven by the `camera_3d` node.
+/// Returns `None` if the entity has no [`Camera`] or no camera node matches its name.
+pub fn render_graph_dot_for_camera(
+    world: &World,
+    camera: Entity,
+    settings: &RenderGraphSettings,
+) -> Option<String> {
+    let render_graph = world.get_resource::<RenderGraph>()?;
+    let camera = world.get::<Camera>(camera)?;
+    let camera_name = normalize(camera.name.as_deref()?);
+
+    let mut graph = DebugGraph::from_render_graph(render_graph);
+    let node_id = graph
+        .nodes
+        .iter()
+        .find(|node| normalize(&node.name) == camera_name)?
+        .id
+        .clone();
+    let target = if camera.window.is_primary() {
+        "primary window".to_string()
+    } else {
+        format!("window {:?}", camera.window)
+    };
+    graph.retain_view(&node_id, &target);
+
+    let graph = transform(graph, settings);
+    Some(DotExporter::new(&settings.dot).export_to_string(&graph))
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|&c| c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+impl DebugGraph {
+    /// Keeps the camera node, the nodes following it and the nodes those depend on,
+    /// without passing through other camera nodes. `target` is added to the name of the camera node.
+    pub fn retain_view(&mut self, camera_node: &str, target: &str) {
+        let mut kept: HashSet<String> = HashSet::default();
+        kept.insert(camera_node.to_string());
+
+        // everything executed after the camera node
+        let mut stack = vec![camera_node.to_string()];
+        while let Some(id) = stack.pop() {
+            for edge in &self.edges {
+                if edge.kind.is_directed() && edge.from == id && kept.insert(edge.to.clone()) {
+                    stack.push(edge.to.clone());
+                }
+            }
+        }
+
+        // and what it depends on, except for other cameras
+        let is_other_camera = |id: &str| {
+            id != camera_node
+                && matches!(self.node(id), Some(node) if node.category() == NodeCategory::Camera)
+        };
+        let mut stack: Vec<String> = kept.iter().cloned().collect();
+        while let Some(id) = stack.pop() {
+            for edge in &self.edges {
+                if edge.kind.is_directed()
+                    && edge.to == id
+                    && !is_other_camera(&edge.from)
+                    && kept.insert(edge.from.clone())
+                {
+                    stack.push(edge.from.clone());
+                }
+            }
+        }
+
+        self.retain_nodes(|node| kept.contains(&node.id));
+        if let Some(node) = self.nodes.iter_mut().find(|node| node.id == camera_node) {
+            node.name = format!("{}\n→ {}", node.name, target);
+        }
+    }
+}
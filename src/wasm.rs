@@ -0,0 +1,33 @@
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+
+/// Prints the text to the browser console, as writing files isn't possible on the web.
+pub fn log_to_console(text: &str) {
+    web_sys::console::log_1(&JsValue::from_str(text));
+}
+
+/// Lets the browser download the text as a file called `file_name`, e.g. `"render_graph.dot"`.
+pub fn download(file_name: &str, text: &str) -> Result<(), JsValue> {
+    let parts = js_sys::Array::of1(&JsValue::from_str(text));
+    let mut options = BlobPropertyBag::new();
+    options.type_("text/vnd.graphviz");
+    let blob = Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+
+    let window =
+        web_sys::window().ok_or_else(|| JsValue::from_str("no window to download from"))?;
+    let document = window
+        .document()
+        .ok_or_else(|| JsValue::from_str("no document to download from"))?;
+    let link: HtmlAnchorElement = document.create_element("a")?.unchecked_into();
+    link.set_href(&url);
+    link.set_download(file_name);
+    link.click();
+
+    // some browsers cancel the download when the url is revoked right away
+    let revoke = Closure::once_into_js(move || {
+        let _ = Url::revoke_object_url(&url);
+    });
+    window.set_timeout_with_callback_and_timeout_and_arguments_0(revoke.unchecked_ref(), 1000)?;
+    Ok(())
+}
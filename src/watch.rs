@@ -0,0 +1,99 @@
+use crate::render_graph::{render_graph_dot_with_settings, RenderGraphSettings};
+use bevy::{prelude::*, render::render_graph::RenderGraph};
+use std::{
+    collections::hash_map::DefaultHasher,
+    error::Error,
+    ffi::OsStr,
+    fs,
+    hash::{Hash, Hasher},
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+/// Rewrites `path` whenever the render graph changes, to be shown by an auto-reloading viewer.
+///
+/// The graph is checked every `interval` seconds. Paths ending in `.svg` are rendered with
+/// graphviz's `dot` command, which needs to be installed, everything else is written as dot.
+pub struct RenderGraphWatchPlugin {
+    pub path: PathBuf,
+    pub settings: RenderGraphSettings,
+    pub interval: f32,
+}
+
+impl RenderGraphWatchPlugin {
+    pub fn new(path: impl Into<PathBuf>) -> RenderGraphWatchPlugin {
+        RenderGraphWatchPlugin {
+            path: path.into(),
+            settings: RenderGraphSettings::default(),
+            interval: 1.0,
+        }
+    }
+}
+
+impl Plugin for RenderGraphWatchPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.insert_resource(RenderGraphWatch {
+            path: self.path.clone(),
+            settings: self.settings.clone(),
+            timer: Timer::from_seconds(self.interval, true),
+            // written on the first check
+            last_hash: None,
+        })
+        .add_system(watch_render_graph.system());
+    }
+}
+
+struct RenderGraphWatch {
+    path: PathBuf,
+    settings: RenderGraphSettings,
+    timer: Timer,
+    last_hash: Option<u64>,
+}
+
+fn watch_render_graph(
+    time: Res<Time>,
+    render_graph: Res<RenderGraph>,
+    mut watch: ResMut<RenderGraphWatch>,
+) {
+    if !watch.timer.tick(time.delta()).just_finished() && watch.last_hash.is_some() {
+        return;
+    }
+
+    let dot = render_graph_dot_with_settings(&render_graph, &watch.settings);
+    let mut hasher = DefaultHasher::new();
+    dot.hash(&mut hasher);
+    let hash = hasher.finish();
+    if watch.last_hash == Some(hash) {
+        return;
+    }
+    watch.last_hash = Some(hash);
+
+    match write_output(&watch.path, &dot) {
+        Ok(()) => bevy::log::info!("render graph changed, wrote {}", watch.path.display()),
+        Err(error) => bevy::log::error!("failed to write {}: {}", watch.path.display(), error),
+    }
+}
+
+fn write_output(path: &Path, dot: &str) -> Result<(), Box<dyn Error>> {
+    if path.extension().and_then(OsStr::to_str) != Some("svg") {
+        return Ok(fs::write(path, dot)?);
+    }
+
+    let mut child = Command::new("dot")
+        .arg("-Tsvg")
+        .arg("-o")
+        .arg(path)
+        .stdin(Stdio::piped())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(dot.as_bytes())?;
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(format!("dot exited with {}", status).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use crate::{
+    dot::{font_tag, html_escape, quote, DotGraph},
+    dot_exporter::DotSettings,
+};
+use bevy::{
+    ecs::{archetype::ArchetypeId, world::World},
+    utils::HashMap,
+};
+use std::fmt;
+
+/// Entity and memory statistics per component type, to spot components which pile up over time.
+/// Resources aren't counted.
+#[derive(Debug, Clone, Default)]
+pub struct WorldSummary {
+    pub entities: usize,
+    pub archetypes: usize,
+    /// sorted by the number of entities, most common first
+    pub components: Vec<ComponentSummary>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ComponentSummary {
+    pub name: String,
+    pub entities: usize,
+    pub archetypes: usize,
+    /// size of the component times the number of entities, not counting storage overhead
+    pub estimated_bytes: usize,
+}
+
+pub fn world_summary(world: &World) -> WorldSummary {
+    let components = world.components();
+    let mut indices = HashMap::default();
+    let mut summaries: Vec<ComponentSummary> = Vec::new();
+    let mut entities = 0;
+    let mut archetypes = 0;
+
+    // resources live in an archetype of their own, which doesn't hold entities
+    let entity_archetypes = world
+        .archetypes()
+        .iter()
+        .filter(|archetype| archetype.id() != ArchetypeId::resource());
+    for archetype in entity_archetypes {
+        entities += archetype.len();
+        archetypes += 1;
+        for id in archetype.components() {
+            let info = match components.get_info(id) {
+                Some(info) => info,
+                None => continue,
+            };
+            let index = *indices.entry(id).or_insert_with(|| {
+                summaries.push(ComponentSummary {
+                    name: info.name().to_string(),
+                    entities: 0,
+                    archetypes: 0,
+                    estimated_bytes: 0,
+                });
+                summaries.len() - 1
+            });
+            let summary = &mut summaries[index];
+            summary.entities += archetype.len();
+            summary.archetypes += 1;
+            summary.estimated_bytes += archetype.len() * info.layout().size();
+        }
+    }
+
+    summaries.sort_by(|a, b| b.entities.cmp(&a.entities).then(a.name.cmp(&b.name)));
+
+    WorldSummary {
+        entities,
+        archetypes,
+        components: summaries,
+    }
+}
+
+const COLUMNS: [&str; 4] = ["component", "entities", "archetypes", "estimated bytes"];
+
+impl WorldSummary {
+    fn rows(&self) -> impl Iterator<Item = [String; 4]> + '_ {
+        self.components.iter().map(|component| {
+            [
+                component.name.clone(),
+                component.entities.to_string(),
+                component.archetypes.to_string(),
+                component.estimated_bytes.to_string(),
+            ]
+        })
+    }
+
+    /// A single node showing the summary as a table.
+    pub fn dot(&self) -> String {
+        self.dot_with_settings(&DotSettings::default())
+    }
+
+    /// Like [`WorldSummary::dot`], using the [`DotSettings::fonts`].
+    pub fn dot_with_settings(&self, settings: &DotSettings) -> String {
+        let fonts = &settings.fonts;
+        let cell =
+            |text: &str, align: &str| format!("<TD ALIGN=\"{}\">{}</TD>", align, html_escape(text));
+        let header: String = COLUMNS
+            .iter()
+            .map(|column| format!("<TD><B>{}</B></TD>", column))
+            .collect();
+        let rows: String = self
+            .rows()
+            .map(|row| {
+                let cells: String = row
+                    .iter()
+                    .enumerate()
+                    .map(|(i, text)| cell(text, if i == 0 { "LEFT" } else { "RIGHT" }))
+                    .collect();
+                format!("<TR>{}</TR>", cells)
+            })
+            .collect();
+        let title = font_tag(
+            &format!(
+                "{} entities in {} archetypes",
+                self.entities, self.archetypes
+            ),
+            &fonts.title_color,
+            fonts.title_size,
+        );
+        let label = format!(
+            "<<TABLE BORDER=\"0\" CELLBORDER=\"1\" CELLSPACING=\"0\"><TR><TD COLSPAN=\"4\">{}</TD></TR><TR>{}</TR>{}</TABLE>>",
+            title, header, rows
+        );
+
+        let mut dot = DotGraph::from_writer(Vec::new(), &quote("World"), &[]);
+        dot.node_attributes(&[
+            ("fontname", &quote(&fonts.family)),
+            ("fontsize", &fonts.detail_size.to_string()),
+        ]);
+        dot.add_node("summary", &[("label", &label), ("shape", "plaintext")]);
+        dot.finish()
+    }
+
+    pub fn csv(&self) -> String {
+        let mut csv = COLUMNS.join(",");
+        csv.push('\n');
+        for row in self.rows() {
+            // type paths may contain commas in their generics
+            csv.push_str(&format!(
+                "\"{}\",{},{},{}\n",
+                row[0].replace('"', "\"\""),
+                row[1],
+                row[2],
+                row[3]
+            ));
+        }
+        csv
+    }
+}
+
+/// Aligned plain text table.
+impl fmt::Display for WorldSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} entities in {} archetypes",
+            self.entities, self.archetypes
+        )?;
+        let mut rows = vec![[
+            COLUMNS[0].to_string(),
+            COLUMNS[1].to_string(),
+            COLUMNS[2].to_string(),
+            COLUMNS[3].to_string(),
+        ]];
+        rows.extend(self.rows());
+        let mut widths = [0; 4];
+        for row in &rows {
+            for (width, text) in widths.iter_mut().zip(row) {
+                *width = (*width).max(text.len());
+            }
+        }
+
+        for row in &rows {
+            writeln!(
+                f,
+                "{:<w0$}  {:>w1$}  {:>w2$}  {:>w3$}",
+                row[0],
+                row[1],
+                row[2],
+                row[3],
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2],
+                w3 = widths[3]
+            )?;
+        }
+        Ok(())
+    }
+}